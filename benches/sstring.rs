@@ -4,7 +4,7 @@
 extern crate test;
 use test::{Bencher, black_box};
 
-use stacked::{SVec, SString32};
+use stacked::{SVec, SVec32, SString32};
 
 #[bench]
 fn string1(b:&mut Bencher) {
@@ -26,6 +26,23 @@ fn sstring1(b:&mut Bencher) {
         for _ in 1..100 {
             let mut s = SString32::new();
             let cap = s.cap();
+            while s.len()<cap { s.push('1').unwrap(); }
+
+            black_box(s);
+        }
+        let z = 444; black_box(z);
+    });
+}
+
+
+// The old 'SString32 = SVec32<u8>' alias, for comparison with the StackString newtype above:
+#[bench]
+fn svec_u8_1(b:&mut Bencher) {
+    b.iter(|| {
+        let a = 333; black_box(a);
+        for _ in 1..100 {
+            let mut s = SVec32::<u8>::new();
+            let cap = s.cap();
             while s.len()<cap { s.push(b'1').unwrap(); }
 
             black_box(s);
@@ -34,3 +51,25 @@ fn sstring1(b:&mut Bencher) {
     });
 }
 
+#[bench]
+fn push_int1(b:&mut Bencher) {
+    b.iter(|| {
+        for i in 1..100u64 {
+            let mut s = SString32::new();
+            s.push_int(black_box(i*12345)).unwrap();
+            black_box(s);
+        }
+    });
+}
+
+#[bench]
+fn write_int1(b:&mut Bencher) {
+    use std::fmt::Write;
+    b.iter(|| {
+        for i in 1..100u64 {
+            let mut s = SString32::new();
+            write!(s, "{}", black_box(i*12345)).unwrap();
+            black_box(s);
+        }
+    });
+}
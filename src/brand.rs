@@ -0,0 +1,148 @@
+use crate::{SVec, StackVec, StackedError};
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+
+// Branded indexes, in the style of GhostCell and the 'indexing' crate.
+//
+// StackVec::brand() hands your closure a Branded view with a brand-new lifetime 'id that can't be unified with any
+// other.  Every Idx<'id> you get from that view is stamped with the same 'id, so the compiler won't let you use it
+// with any other view.  The view only supports the Append-Only Interface (the length never goes down), so an Idx
+// that was in bounds when it was made stays in bounds until the closure returns -- which means lookups can skip the
+// bounds check, and you never have to write 'unsafe' yourself.
+//
+// This only works with StackVec (not any SVec), because the unchecked lookups have to trust len(), and we can't
+// trust an SVec impl that lives outside this crate.
+
+// Invariant in 'id, so one brand can't be shortened or lengthened into another.
+type Brand<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+// An index that is known to be in bounds for the Branded view with the same 'id.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Idx<'id> {
+    i: usize,
+    _brand: Brand<'id>,
+}
+impl<'id> Idx<'id> {
+    #[inline]
+    pub fn index(self) -> usize { self.i }
+}
+impl<'id> fmt::Debug for Idx<'id> {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "Idx({})", self.i)
+    }
+}
+
+// An append-only view of a StackVec.  See StackVec::brand().
+pub struct Branded<'id, 'a, T, const N:usize> {
+    svec: &'a mut StackVec<T,N>,
+    _brand: Brand<'id>,
+}
+
+impl<T, const N:usize> StackVec<T,N> {
+    /// Runs 'f' with a Branded view of this SVec.  Idx values can't escape the closure.
+    ///
+    /// These doc-tests guard the brand itself (if 'id ever became covariant, they would start compiling).
+    /// An Idx from one view can't be used with another:
+    ///
+    /// ```compile_fail
+    /// use stacked::{SVec, SVec4};
+    /// let (mut a, mut b) = (SVec4::<u8>::new(), SVec4::<u8>::new());
+    /// a.brand(|mut x| {
+    ///     let i = x.push(1).unwrap();
+    ///     b.brand(|y| { let _ = y[i]; });
+    /// });
+    /// ```
+    ///
+    /// And an Idx can't outlive its closure (so it can't be used after the SVec shrinks):
+    ///
+    /// ```compile_fail
+    /// use stacked::{SVec, SVec4};
+    /// let mut a = SVec4::<u8>::new();
+    /// let i = a.brand(|mut x| x.push(1).unwrap());
+    /// a.clear();
+    /// let _ = i;
+    /// ```
+    #[inline]
+    pub fn brand<'a, R, F>(&'a mut self, f:F) -> R where F:for<'id> FnOnce(Branded<'id,'a,T,N>) -> R {
+        f(Branded{ svec:self, _brand:PhantomData })
+    }
+}
+
+impl<'id, 'a, T, const N:usize> Branded<'id,'a,T,N> {
+    #[inline]
+    fn mint(&self, i:usize) -> Idx<'id> { Idx{ i, _brand:PhantomData } }
+
+    #[inline]
+    pub fn cap(&self) -> usize { N }
+    #[inline]
+    pub fn len(&self) -> usize { self.svec.length }
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.svec.length==0 }
+
+    // ---- Append-Only Interface ----
+    #[inline]
+    pub fn push(&mut self, t:T) -> Result<Idx<'id>,(T,StackedError)> {
+        let i = self.svec.push(t)?;
+        Ok(self.mint(i))
+    }
+    // The one and only bounds check:  turns a raw index into a branded one.
+    #[inline]
+    pub fn idx(&self, i:usize) -> Option<Idx<'id>> {
+        if i>=self.svec.length { return None }
+        Some(self.mint(i))
+    }
+    #[inline]
+    pub fn first(&self) -> Option<Idx<'id>> { self.idx(0) }
+    #[inline]
+    pub fn last(&self) -> Option<Idx<'id>> {
+        if self.svec.length==0 { return None }
+        self.idx(self.svec.length-1)
+    }
+    // All the indexes that are currently valid.  (Anything pushed later isn't included.)
+    #[inline]
+    pub fn indices(&self) -> impl DoubleEndedIterator<Item=Idx<'id>> + ExactSizeIterator {
+        (0..self.svec.length).map(|i| Idx{ i, _brand:PhantomData })
+    }
+
+    #[inline]
+    pub fn get(&self, i:Idx<'id>) -> &T {
+        // Safe because 'i' was checked against this same view, and the length never goes down.
+        unsafe { &*self.svec.as_ptr().add(i.i) }
+    }
+    #[inline]
+    pub fn get_mut(&mut self, i:Idx<'id>) -> &mut T {
+        unsafe { &mut *self.svec.as_mut_ptr().add(i.i) }
+    }
+    #[inline]
+    pub fn swap(&mut self, a:Idx<'id>, b:Idx<'id>) {
+        // One base pointer for both:  a second as_mut_ptr() would reborrow the whole SVec and invalidate the first.
+        let p = self.svec.as_mut_ptr();
+        unsafe { std::ptr::swap(p.add(a.i), p.add(b.i)); }
+    }
+
+    // Slices are fine to hand out:  they can change items, but not the length.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] { self.svec.as_slice() }
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] { self.svec.as_mut_slice() }
+}
+
+impl<'id, 'a, T, const N:usize> Index<Idx<'id>> for Branded<'id,'a,T,N> {
+    type Output = T;
+    #[inline]
+    fn index(&self, i:Idx<'id>) -> &T { self.get(i) }
+}
+impl<'id, 'a, T, const N:usize> IndexMut<Idx<'id>> for Branded<'id,'a,T,N> {
+    #[inline]
+    fn index_mut(&mut self, i:Idx<'id>) -> &mut T { self.get_mut(i) }
+}
+
+impl<'id, 'a, T, const N:usize> fmt::Debug for Branded<'id,'a,T,N> where T:fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "Branded")?;
+        fmt::Debug::fmt(&*self.svec, f)
+    }
+}
@@ -0,0 +1,82 @@
+use std::error::Error;
+use std::fmt;
+use std::str::Utf8Error;
+use std::string::FromUtf8Error;
+
+
+// One error type for the whole crate, so callers can match on what went wrong instead of comparing strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StackedError {
+    // Tried to add more items than the container can hold.  'rejected' is the number of items that did not fit.
+    Overflow { cap:usize, rejected:usize },
+    // An index was past the end of the initialized items.
+    OutOfBounds { index:usize, len:usize },
+    // Tried to take an item out of an empty container.
+    Underflow,
+    // The bytes are not valid UTF-8.  Everything before 'valid_up_to' is fine.
+    InvalidUtf8 { valid_up_to:usize },
+    // A string index landed in the middle of a multi-byte char.
+    NotCharBoundary { index:usize },
+    // A Display/Debug impl returned fmt::Error while we were formatting into a StackString.
+    Fmt,
+}
+
+impl fmt::Display for StackedError {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            StackedError::Overflow{cap, rejected} => write!(f, "overflow: capacity is {}, {} item(s) rejected", cap, rejected),
+            StackedError::OutOfBounds{index, len} => write!(f, "out-of-bounds: index is {} but len is {}", index, len),
+            StackedError::Underflow => write!(f, "underflow"),
+            StackedError::InvalidUtf8{valid_up_to} => write!(f, "invalid UTF-8 after byte {}", valid_up_to),
+            StackedError::NotCharBoundary{index} => write!(f, "byte {} is not a char boundary", index),
+            StackedError::Fmt => write!(f, "formatting error"),
+        }
+    }
+}
+
+impl Error for StackedError {}
+
+// Fallible operations hand the rejected item back along with the error.
+// This lets you use '?' when you don't care about getting the item back.
+impl<T> From<(T, StackedError)> for StackedError {
+    #[inline]
+    fn from((_, err):(T, StackedError)) -> Self { err }
+}
+
+impl From<Utf8Error> for StackedError {
+    fn from(err:Utf8Error) -> Self {
+        StackedError::InvalidUtf8{ valid_up_to:err.valid_up_to() }
+    }
+}
+impl From<FromUtf8Error> for StackedError {
+    fn from(err:FromUtf8Error) -> Self {
+        err.utf8_error().into()
+    }
+}
+
+
+// Returned when a bulk append runs out of room.  Nothing is lost:  'accepted' items were appended,
+// and 'leftover' holds everything that did not fit (a slice or an iterator, depending on what you passed in).
+pub struct ExtendError<L> {
+    pub accepted: usize,
+    pub leftover: L,
+    pub error: StackedError,
+}
+
+// Iterators usually aren't Debug, so don't require it.
+impl<L> fmt::Debug for ExtendError<L> {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("ExtendError").field("accepted", &self.accepted).field("error", &self.error).finish_non_exhaustive()
+    }
+}
+impl<L> fmt::Display for ExtendError<L> {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{} (after accepting {})", self.error, self.accepted)
+    }
+}
+impl<L> Error for ExtendError<L> {}
+
+impl<L> From<ExtendError<L>> for StackedError {
+    #[inline]
+    fn from(err:ExtendError<L>) -> Self { err.error }
+}
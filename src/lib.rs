@@ -1,39 +1,47 @@
-#![feature(test, const_in_array_repeat_expressions)]
+#![feature(test)]
 
 extern crate test;
 
-#[macro_use]
+mod error;
 mod svec;
-// mod sstring;  // Newtypes are way too expensive!  Just alias to SVec instead.
-// mod sref;     // I'll probably do this later.  Right now, it's just simpler-to-understand and more efficient to just use raw indexes.
-
-pub use self::svec::{SVec, IntoIter};
-
-use kerr::KErr;
-
-use std::fmt;
-use std::mem;
-use std::ptr;
-use std::ops::{Index, IndexMut};
-use std::iter;
-
-
-// I am looking forward to const_generics : https://github.com/rust-lang/rust/issues/44580
-
-def_stackvec!(   1,    SVec1,    SString1);
-def_stackvec!(   2,    SVec2,    SString2);
-def_stackvec!(   4,    SVec4,    SString4);
-def_stackvec!(   8,    SVec8,    SString8);
-def_stackvec!(  16,   SVec16,   SString16);
-def_stackvec!(  32,   SVec32,   SString32);
-def_stackvec!(  64,   SVec64,   SString64);
-def_stackvec!( 128,  SVec128,  SString128);
-def_stackvec!( 256,  SVec256,  SString256);
-def_stackvec!( 512,  SVec512,  SString512);
-def_stackvec!(1024, SVec1024, SString1024);
-def_stackvec!(2048, SVec2048, SString2048);
-def_stackvec!(4096, SVec4096, SString4096);
-def_stackvec!(8192, SVec8192, SString8192);
+mod sstring;
+mod num;
+mod sdeque;
+mod policy;
+mod smap;
+mod sset;
+mod sref;     // Optional.  Raw indexes are still simpler-to-understand and more efficient if you stick to the Append-Only Interface.
+mod brand;    // Raw indexes, but checked at compile time instead of at run time.
+
+pub use self::error::{StackedError, ExtendError};
+pub use self::svec::{SVec, StackVec, Iter, IterMut, IntoIter, IterOwned, Drain, Leftover};
+pub use self::sstring::{StackString, Truncation, Writer};
+pub use self::sdeque::{StackDeque, DequeIter, DequeIterMut, DequeIntoIter};
+pub use self::policy::{Bounded, Buffer, Policy, Reject, Panic, OverwriteOldest, DropNewest};
+pub use self::smap::{SMap, Entry, OccupiedEntry, VacantEntry, MapIter, MapIterMut};
+pub use self::sset::{SSet, Union, Intersection, Difference, SymmetricDifference};
+pub use self::num::{Integer, Float};
+pub use self::sref::{SRef, Tracked};
+pub use self::brand::{Branded, Idx};
+
+
+// StackVec, StackString and StackDeque can hold any capacity (StackVec<T,100> is fine), but these are the
+// names that we've been using all along, so keep them around as aliases:
+
+pub type SVec1<T>    = StackVec<T,   1>;    pub type SString1    = StackString<   1>;    pub type SDeque1<T>    = StackDeque<T,   1>;
+pub type SVec2<T>    = StackVec<T,   2>;    pub type SString2    = StackString<   2>;    pub type SDeque2<T>    = StackDeque<T,   2>;
+pub type SVec4<T>    = StackVec<T,   4>;    pub type SString4    = StackString<   4>;    pub type SDeque4<T>    = StackDeque<T,   4>;
+pub type SVec8<T>    = StackVec<T,   8>;    pub type SString8    = StackString<   8>;    pub type SDeque8<T>    = StackDeque<T,   8>;
+pub type SVec16<T>   = StackVec<T,  16>;    pub type SString16   = StackString<  16>;    pub type SDeque16<T>   = StackDeque<T,  16>;
+pub type SVec32<T>   = StackVec<T,  32>;    pub type SString32   = StackString<  32>;    pub type SDeque32<T>   = StackDeque<T,  32>;
+pub type SVec64<T>   = StackVec<T,  64>;    pub type SString64   = StackString<  64>;    pub type SDeque64<T>   = StackDeque<T,  64>;
+pub type SVec128<T>  = StackVec<T, 128>;    pub type SString128  = StackString< 128>;    pub type SDeque128<T>  = StackDeque<T, 128>;
+pub type SVec256<T>  = StackVec<T, 256>;    pub type SString256  = StackString< 256>;    pub type SDeque256<T>  = StackDeque<T, 256>;
+pub type SVec512<T>  = StackVec<T, 512>;    pub type SString512  = StackString< 512>;    pub type SDeque512<T>  = StackDeque<T, 512>;
+pub type SVec1024<T> = StackVec<T,1024>;    pub type SString1024 = StackString<1024>;    pub type SDeque1024<T> = StackDeque<T,1024>;
+pub type SVec2048<T> = StackVec<T,2048>;    pub type SString2048 = StackString<2048>;    pub type SDeque2048<T> = StackDeque<T,2048>;
+pub type SVec4096<T> = StackVec<T,4096>;    pub type SString4096 = StackString<4096>;    pub type SDeque4096<T> = StackDeque<T,4096>;
+pub type SVec8192<T> = StackVec<T,8192>;    pub type SString8192 = StackString<8192>;    pub type SDeque8192<T> = StackDeque<T,8192>;
 
 
 #[cfg(test)]
@@ -43,20 +51,17 @@ mod internal_tests {
     // An experiment, to see how 'drop' works when overwriting values,
     // and also to verify that we really are mutating the memory we expect:
 
-    #[derive(PartialEq)]
+    #[derive(PartialEq, Debug, Default)]
     struct Dropper(i32);
     impl Drop for Dropper {
         fn drop(&mut self) {
             eprintln!("in Dropper.drop: {}", self.0);
         }
     }
-    impl Default for Dropper {
-        fn default() -> Self { Self(0) }
-    }
 
     impl<T> SVec4<T> where T:PartialEq {
-        fn zet(&self, i:usize, t:T) {  // Intentionally unsafe design -- I'm mutating via a shared reference so I can verify that I'm really modifying the memory i expect.
-            unsafe { *(self.data[i].as_ref().unwrap() as *const T as *mut T) = t; }
+        fn zet(&mut self, i:usize, t:T) {  // Writes straight into the backing storage so I can verify that I'm really modifying the memory i expect.
+            unsafe { *self.data[i].assume_init_mut() = t; }  // (This used to mutate through a shared reference, but the compiler now rejects that as UB.)
         }
     }
     //impl<T> Drop for SVec4<T> {
@@ -83,7 +88,7 @@ mod internal_tests {
         vec.zet(0, Dropper(-11));
         assert_eq!(vec[i0].0,-11);
 
-        // Doesn't work anymore after switching to None default values:
+        // Doesn't work anymore after switching to uninitialized storage (assume_init_mut on an uninitialized slot is UB):
         //vec.zet(3, Dropper(-3));  // Treats existing zero-bytes as a Dropper and drops it.
                                     // We're lucky zeroed memory happens to be a valid i32, otherwise BAD THINGS could happen!
                                     // This item's drop() won't be called because SVec assumes it has not been initialized!
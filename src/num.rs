@@ -0,0 +1,96 @@
+// Number formatting for StackString::push_int/push_hex/push_float.
+// Everything renders into a small local buffer first, so we can check the length before touching the string.
+
+
+// Big enough for any integer we support:  i128::MIN is 40 bytes in decimal (with the sign), and u128::MAX is 32 in hex.
+pub(crate) const INT_BUF : usize = 40;
+
+const DEC_DIGITS_LUT : &[u8; 200] = b"\
+    0001020304050607080910111213141516171819\
+    2021222324252627282930313233343536373839\
+    4041424344454647484950515253545556575859\
+    6061626364656667686970717273747576777879\
+    8081828384858687888990919293949596979899";
+const HEX_DIGITS : &[u8; 16] = b"0123456789abcdef";
+
+/// Implemented for every primitive integer type.
+///
+/// Sealed, because push_int() and push_hex() trust it to write only ASCII.  Outside this crate, this doesn't compile:
+///
+/// ```compile_fail
+/// #[derive(Clone, Copy)]
+/// struct Evil;
+/// impl stacked::Integer for Evil {
+///     fn write_dec(self, buf:&mut [u8; 40]) -> usize { buf[39] = 0xFF;  39 }
+///     fn write_hex(self, buf:&mut [u8; 40]) -> usize { self.write_dec(buf) }
+/// }
+/// ```
+pub trait Integer : Copy + private::Sealed {
+    // These write right-aligned into 'buf' and return the index of the first byte.
+    #[doc(hidden)]
+    fn write_dec(self, buf:&mut [u8; INT_BUF]) -> usize;
+    #[doc(hidden)]
+    fn write_hex(self, buf:&mut [u8; INT_BUF]) -> usize;  // Negative numbers come out in two's complement, like {:x}.
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_integer {
+    ( $($t:ty => $u:ty),* ) => { $(
+        impl private::Sealed for $t {}
+        impl Integer for $t {
+            #[inline]
+            #[allow(unused_comparisons)]
+            fn write_dec(self, buf:&mut [u8; INT_BUF]) -> usize {
+                let neg = self<0;
+                let mut n = if neg { (self as $u).wrapping_neg() } else { self as $u };
+                let mut i = INT_BUF;
+                while n>=100 {
+                    let d = (n%100) as usize * 2;
+                    n/=100;
+                    i-=2;
+                    buf[i..i+2].copy_from_slice(&DEC_DIGITS_LUT[d..d+2]);
+                }
+                if n>=10 {
+                    let d = n as usize * 2;
+                    i-=2;
+                    buf[i..i+2].copy_from_slice(&DEC_DIGITS_LUT[d..d+2]);
+                } else {
+                    i-=1;
+                    buf[i] = b'0'+n as u8;
+                }
+                if neg { i-=1; buf[i] = b'-'; }
+                i
+            }
+            #[inline]
+            fn write_hex(self, buf:&mut [u8; INT_BUF]) -> usize {
+                let mut n = self as $u;
+                let mut i = INT_BUF;
+                loop {
+                    i-=1;
+                    buf[i] = HEX_DIGITS[(n&0xf) as usize];
+                    n>>=4;
+                    if n==0 { return i }
+                }
+            }
+        }
+    )* }
+}
+impl_integer!(u8=>u8, u16=>u16, u32=>u32, u64=>u64, u128=>u128, usize=>usize,
+              i8=>u8, i16=>u16, i32=>u32, i64=>u64, i128=>u128, isize=>usize);
+
+// f32 and f64.  Formatting is done by the 'ryu' crate, which produces the shortest string that parses back to the same value.
+pub trait Float : Copy {
+    #[doc(hidden)]
+    fn write_shortest(self, buf:&mut ryu::Buffer) -> &str;
+}
+impl Float for f32 {
+    #[inline]
+    fn write_shortest(self, buf:&mut ryu::Buffer) -> &str { buf.format(self) }
+}
+impl Float for f64 {
+    #[inline]
+    fn write_shortest(self, buf:&mut ryu::Buffer) -> &str { buf.format(self) }
+}
@@ -0,0 +1,149 @@
+use crate::{SVec, StackVec, StackDeque, StackedError};
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+
+// What to do when a push doesn't fit.  Wrap a container in Bounded<_,P> and push() behaves according to P:
+//
+//     Reject          -> Result<(),(T,StackedError)>   Same as a plain push:  you get the item back.
+//     Panic           -> ()                            Panics with the overflow error.  Handy in tests.
+//     OverwriteOldest -> Option<T>                     Makes room by removing the oldest item, and hands it to you.
+//     DropNewest      -> Option<T>                     Leaves the container alone, and hands the new item back to you.
+//
+// The Option ones never fail, so call sites don't need to deal with a Result.  Ignore the Option if you don't care what got evicted.
+pub trait Policy {
+    type Output<T>;
+    fn push<B>(buf:&mut B, t:B::Item) -> Self::Output<B::Item> where B:Buffer;
+    // For extend() and collect(), which have nowhere to hand the output back.  Reject overrides this to panic (see
+    // StackVec's FromIterator);  for the others, dropping the output is just what the policy says.
+    #[inline]
+    fn push_discard<B>(buf:&mut B, t:B::Item) where B:Buffer { Self::push(buf, t); }
+}
+
+// The containers that Bounded can wrap.
+pub trait Buffer {
+    type Item;
+    fn push_newest(&mut self, t:Self::Item) -> Result<(),(Self::Item,StackedError)>;
+    fn pop_oldest(&mut self) -> Option<Self::Item>;
+}
+// Works, but pop_oldest() shifts everything down.  If you're going to use OverwriteOldest a lot, a StackDeque is O(1).
+impl<T, const N:usize> Buffer for StackVec<T,N> {
+    type Item = T;
+    #[inline]
+    fn push_newest(&mut self, t:T) -> Result<(),(T,StackedError)> { self.push(t).map(|_| ()) }
+    #[inline]
+    fn pop_oldest(&mut self) -> Option<T> { self.try_remove(0) }
+}
+impl<T, const N:usize> Buffer for StackDeque<T,N> {
+    type Item = T;
+    #[inline]
+    fn push_newest(&mut self, t:T) -> Result<(),(T,StackedError)> { self.push_back(t) }
+    #[inline]
+    fn pop_oldest(&mut self) -> Option<T> { self.try_pop_front() }
+}
+
+pub struct Reject;
+impl Policy for Reject {
+    type Output<T> = Result<(),(T,StackedError)>;
+    #[inline]
+    fn push<B>(buf:&mut B, t:B::Item) -> Self::Output<B::Item> where B:Buffer { buf.push_newest(t) }
+    #[inline]
+    fn push_discard<B>(buf:&mut B, t:B::Item) where B:Buffer { Panic::push(buf, t) }
+}
+
+pub struct Panic;
+impl Policy for Panic {
+    type Output<T> = ();
+    #[inline]
+    fn push<B>(buf:&mut B, t:B::Item) where B:Buffer {
+        if let Err((_,err)) = buf.push_newest(t) { panic!("{}", err); }
+    }
+}
+
+pub struct OverwriteOldest;
+impl Policy for OverwriteOldest {
+    type Output<T> = Option<T>;
+    #[inline]
+    fn push<B>(buf:&mut B, t:B::Item) -> Option<B::Item> where B:Buffer {
+        let t = match buf.push_newest(t) {
+            Ok(()) => return None,
+            Err((t,_)) => t,
+        };
+        match buf.pop_oldest() {
+            Some(old) => {
+                if buf.push_newest(t).is_err() { unreachable!() }  // We just made room.
+                Some(old)
+            }
+            None => Some(t),  // Zero capacity:  the new item is the oldest one we could evict.
+        }
+    }
+}
+
+pub struct DropNewest;
+impl Policy for DropNewest {
+    type Output<T> = Option<T>;
+    #[inline]
+    fn push<B>(buf:&mut B, t:B::Item) -> Option<B::Item> where B:Buffer {
+        buf.push_newest(t).err().map(|(t,_)| t)
+    }
+}
+
+
+// A container plus an overflow policy.  push() and extend() follow the policy.  Reads go straight through to the container
+// (via Deref), but there's deliberately no DerefMut:  the container's own push methods don't know about the policy, so
+// changing it directly goes through get_mut(), where it's easy to see.
+pub struct Bounded<B, P> {
+    buf: B,
+    _policy: PhantomData<P>,
+}
+impl<B, P> Bounded<B,P> where B:Buffer, P:Policy {
+    #[inline]
+    pub fn new() -> Self where B:Default { Self::from(B::default()) }
+    #[inline]
+    pub fn into_inner(self) -> B { self.buf }
+    #[inline]
+    pub fn push(&mut self, t:B::Item) -> P::Output<B::Item> { P::push(&mut self.buf, t) }
+    // For pop/clear/etc.  A push through here skips the policy.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut B { &mut self.buf }
+}
+
+// Each item goes through the policy, but the outputs are dropped, so use push() if you want to see what was evicted.
+// Reject and Panic both panic on overflow.
+impl<B, P> Extend<B::Item> for Bounded<B,P> where B:Buffer, P:Policy {
+    fn extend<I>(&mut self, iter:I) where I:IntoIterator<Item=B::Item> {
+        for t in iter { P::push_discard(&mut self.buf, t); }
+    }
+}
+impl<B, P> std::iter::FromIterator<B::Item> for Bounded<B,P> where B:Buffer+Default, P:Policy {
+    fn from_iter<I>(iter:I) -> Self where I:IntoIterator<Item=B::Item> {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+impl<B, P> From<B> for Bounded<B,P> {
+    #[inline]
+    fn from(buf:B) -> Self { Self{ buf, _policy:PhantomData } }
+}
+impl<B, P> Default for Bounded<B,P> where B:Default {
+    #[inline]
+    fn default() -> Self { Self::from(B::default()) }
+}
+impl<B, P> Clone for Bounded<B,P> where B:Clone {
+    #[inline]
+    fn clone(&self) -> Self { Self::from(self.buf.clone()) }
+}
+impl<B, P> Deref for Bounded<B,P> {
+    type Target = B;
+    #[inline]
+    fn deref(&self) -> &B { &self.buf }
+}
+impl<B, P> fmt::Debug for Bounded<B,P> where B:fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt::Debug::fmt(&self.buf, f)
+    }
+}
@@ -0,0 +1,378 @@
+use crate::StackedError;
+
+use std::fmt;
+use std::ptr;
+use std::slice;
+use std::mem::MaybeUninit;
+use std::ops::{Index, IndexMut};
+use std::iter::{self, FusedIterator};
+use std::hash::{Hash, Hasher};
+
+
+// A ring buffer.  The items start at 'head' and wrap around the end of 'data', so both ends are O(1).
+// Like StackVec, only the 'length' slots starting at 'head' hold live values; everything else is garbage.
+pub struct StackDeque<T, const N:usize> {
+    data: [MaybeUninit<T>; N],
+    head: usize,
+    length: usize,
+}
+
+impl<T, const N:usize> StackDeque<T,N> {
+    #[inline]
+    pub fn new() -> Self {
+        Self{ data:[const { MaybeUninit::uninit() }; N],
+              head:0,
+              length:0 }
+    }
+    #[inline]
+    pub fn cap_of_type() -> usize { N }
+    #[inline]
+    pub fn cap(&self) -> usize { N }
+    #[inline]
+    pub fn len(&self) -> usize { self.length }
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.length==0 }
+    #[inline]
+    pub fn is_full(&self) -> bool { self.length==N }
+
+    // Where the i'th item lives in 'data'.  Only valid for i<N, which means head+i<2N, so we never need a real modulo.
+    #[inline]
+    fn slot(&self, i:usize) -> usize {
+        let p = self.head+i;
+        if p>=N { p-N } else { p }
+    }
+    #[inline]
+    fn ptr(&self) -> *const T { self.data.as_ptr() as *const T }
+    #[inline]
+    fn mut_ptr(&mut self) -> *mut T { self.data.as_mut_ptr() as *mut T }
+
+    // ---- Both Ends ----
+    // Same as SVec::push:  on overflow, you get the item back.
+    #[inline]
+    pub fn push_back(&mut self, t:T) -> Result<(),(T,StackedError)> {
+        if self.length>=N { return Err((t, StackedError::Overflow{ cap:N, rejected:1 })); }
+        let p = self.slot(self.length);
+        unsafe { ptr::write(self.mut_ptr().add(p), t); }
+        self.length+=1;
+        Ok(())
+    }
+    #[inline]
+    pub fn push_front(&mut self, t:T) -> Result<(),(T,StackedError)> {
+        if self.length>=N { return Err((t, StackedError::Overflow{ cap:N, rejected:1 })); }
+        self.head = if self.head==0 { N-1 } else { self.head-1 };
+        unsafe { ptr::write(self.mut_ptr().add(self.head), t); }
+        self.length+=1;
+        Ok(())
+    }
+    // Like SVec::pop, the plain versions panic on underflow.  The try_ versions never panic.
+    pub fn pop_back(&mut self) -> T {
+        match self.try_pop_back() {
+            Some(t) => t,
+            None => panic!("{}", StackedError::Underflow),
+        }
+    }
+    #[inline]
+    pub fn try_pop_back(&mut self) -> Option<T> {
+        if self.length==0 { return None }
+        self.length-=1;
+        let p = self.slot(self.length);
+        Some(unsafe { ptr::read(self.ptr().add(p)) })
+    }
+    pub fn pop_front(&mut self) -> T {
+        match self.try_pop_front() {
+            Some(t) => t,
+            None => panic!("{}", StackedError::Underflow),
+        }
+    }
+    #[inline]
+    pub fn try_pop_front(&mut self) -> Option<T> {
+        if self.length==0 { return None }
+        let t = unsafe { ptr::read(self.ptr().add(self.head)) };
+        self.head = self.slot(1);
+        self.length-=1;
+        Some(t)
+    }
+
+    // ---- Access ----
+    // Indexes count from the front, no matter where the items physically are.
+    #[inline]
+    pub fn get(&self, i:usize) -> Option<&T> {
+        if i>=self.length { return None }
+        Some(unsafe { &*self.ptr().add(self.slot(i)) })
+    }
+    #[inline]
+    pub fn get_mut(&mut self, i:usize) -> Option<&mut T> {
+        if i>=self.length { return None }
+        let p = self.slot(i);
+        Some(unsafe { &mut *self.mut_ptr().add(p) })
+    }
+    #[inline]
+    pub fn front(&self) -> Option<&T> { self.get(0) }
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        if self.length==0 { return None }
+        self.get(self.length-1)
+    }
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> { self.get_mut(0) }
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        if self.length==0 { return None }
+        self.get_mut(self.length-1)
+    }
+
+    // The items in order, as two slices.  The second one is empty unless the items wrap around the end of the storage.
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (a,b) = self.split_lens();
+        unsafe { (slice::from_raw_parts(self.ptr().add(self.head), a),
+                  slice::from_raw_parts(self.ptr(), b)) }
+    }
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (a,b) = self.split_lens();
+        let (head, p) = (self.head, self.mut_ptr());
+        unsafe { (slice::from_raw_parts_mut(p.add(head), a),
+                  slice::from_raw_parts_mut(p, b)) }
+    }
+    #[inline]
+    fn split_lens(&self) -> (usize, usize) {
+        let a = self.length.min(N-self.head);
+        (a, self.length-a)
+    }
+    // Moves the items so they are all in one slice.  Does nothing if they already are.
+    // After this, as_slices().1 is empty until the next push_front (or a push_back that wraps).
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if self.head+self.length>N {
+            // Rotating the raw slots is just a bitwise shuffle, so no items are dropped or duplicated.
+            self.data.rotate_left(self.head);
+            self.head = 0;
+        }
+        self.as_mut_slices().0
+    }
+
+    #[inline]
+    pub fn iter(&self) -> DequeIter<'_,T> {
+        let (a,b) = self.as_slices();
+        DequeIter{ a:a.iter(), b:b.iter() }
+    }
+    #[inline]
+    pub fn iter_mut(&mut self) -> DequeIterMut<'_,T> {
+        let (a,b) = self.as_mut_slices();
+        DequeIterMut{ a:a.iter_mut(), b:b.iter_mut() }
+    }
+
+    // ---- Shrinking ----
+    // Keeps the first 'len' items.  Does nothing if 'len' is not smaller than the current length.
+    pub fn truncate(&mut self, len:usize) {
+        if len>=self.length { return }
+        let (a,b) = self.as_mut_slices();
+        let (front, back) : (*mut [T], *mut [T]) = if len<=a.len() { (&mut a[len..], b) }
+                                                   else { let n=a.len(); (&mut [], &mut b[len-n..]) };
+        self.length = len;  // Before dropping, like StackVec::truncate().
+        // The tail can be in two pieces.  If dropping the front piece panics, the guard still drops the back piece.
+        let _back = DropSlice(back);
+        unsafe { ptr::drop_in_place(front); }
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.head = 0;
+    }
+}
+
+struct DropSlice<T>(*mut [T]);
+impl<T> Drop for DropSlice<T> {
+    fn drop(&mut self) { unsafe { ptr::drop_in_place(self.0); } }
+}
+
+impl<T, const N:usize> Drop for StackDeque<T,N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N:usize> Index<usize> for StackDeque<T,N> {
+    type Output = T;
+    #[inline]
+    fn index(&self, i:usize) -> &T {
+        match self.get(i) {
+            Some(t) => t,
+            None => panic!("{}", StackedError::OutOfBounds{ index:i, len:self.length }),
+        }
+    }
+}
+impl<T, const N:usize> IndexMut<usize> for StackDeque<T,N> {
+    #[inline]
+    fn index_mut(&mut self, i:usize) -> &mut T {
+        let len = self.length;
+        match self.get_mut(i) {
+            Some(t) => t,
+            None => panic!("{}", StackedError::OutOfBounds{ index:i, len }),
+        }
+    }
+}
+
+// Pops from the front as it goes.
+impl<T, const N:usize> IntoIterator for StackDeque<T,N> {
+    type Item = T;
+    type IntoIter = DequeIntoIter<T,N>;
+    #[inline]
+    fn into_iter(self) -> DequeIntoIter<T,N> { DequeIntoIter(self) }
+}
+impl<'a, T, const N:usize> IntoIterator for &'a StackDeque<T,N> {
+    type Item = &'a T;
+    type IntoIter = DequeIter<'a,T>;
+    #[inline]
+    fn into_iter(self) -> DequeIter<'a,T> { self.iter() }
+}
+impl<'a, T, const N:usize> IntoIterator for &'a mut StackDeque<T,N> {
+    type Item = &'a mut T;
+    type IntoIter = DequeIterMut<'a,T>;
+    #[inline]
+    fn into_iter(self) -> DequeIterMut<'a,T> { self.iter_mut() }
+}
+
+// These panic on overflow (see StackVec's FromIterator).  Check is_full() or use push_back() if you need to handle that.
+impl<T, const N:usize> iter::FromIterator<T> for StackDeque<T,N> {
+    fn from_iter<I>(iter:I) -> Self where I:IntoIterator<Item=T> {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+impl<T, const N:usize> Extend<T> for StackDeque<T,N> {
+    fn extend<I>(&mut self, iter:I) where I:IntoIterator<Item=T> {
+        for t in iter {
+            if let Err((_,err)) = self.push_back(t) { panic!("{}", err); }
+        }
+    }
+}
+
+// Two deques are equal if they hold equal items in the same order, no matter where the items physically are.
+impl<T, U, const N:usize, const M:usize> PartialEq<StackDeque<U,M>> for StackDeque<T,N> where T:PartialEq<U> {
+    fn eq(&self, other:&StackDeque<U,M>) -> bool {
+        self.length==other.length && self.iter().zip(other.iter()).all(|(a,b)| a==b)
+    }
+}
+impl<T, const N:usize> Eq for StackDeque<T,N> where T:Eq {}
+impl<T, const N:usize> Hash for StackDeque<T,N> where T:Hash {
+    fn hash<H>(&self, state:&mut H) where H:Hasher {
+        self.length.hash(state);
+        for t in self.iter() { t.hash(state); }
+    }
+}
+
+impl<T, const N:usize> Default for StackDeque<T,N> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+impl<T, const N:usize> Clone for StackDeque<T,N> where T:Clone {
+    fn clone(&self) -> Self {
+        let mut out = Self::new();
+        for t in self.iter() {
+            if out.push_back(t.clone()).is_err() { unreachable!() }
+        }
+        out
+    }
+}
+
+impl<T, const N:usize> fmt::Debug for StackDeque<T,N> where T:fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut nonempty = false;
+        write!(f, "SDeque{}[", N)?;
+        for t in self.iter() {
+            if nonempty { write!(f, ",")?; }
+            nonempty = true;
+            write!(f, " {:?}", t)?;
+        }
+        if nonempty { write!(f, " ")?; }
+        write!(f, "]")?;
+        Ok(())
+    }
+}
+
+
+// Front-to-back over the two halves from as_slices().
+pub struct DequeIter<'a,T> {
+    a: slice::Iter<'a,T>,
+    b: slice::Iter<'a,T>,
+}
+impl<'a,T> Iterator for DequeIter<'a,T> {
+    type Item = &'a T;
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        match self.a.next() {
+            Some(t) => Some(t),
+            None => self.b.next(),
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.a.len()+self.b.len();
+        (n, Some(n))
+    }
+}
+impl<'a,T> DoubleEndedIterator for DequeIter<'a,T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        match self.b.next_back() {
+            Some(t) => Some(t),
+            None => self.a.next_back(),
+        }
+    }
+}
+impl<T> ExactSizeIterator for DequeIter<'_,T> {}
+impl<T> FusedIterator for DequeIter<'_,T> {}
+impl<T> Clone for DequeIter<'_,T> {
+    #[inline]
+    fn clone(&self) -> Self { Self{ a:self.a.clone(), b:self.b.clone() } }
+}
+
+pub struct DequeIterMut<'a,T> {
+    a: slice::IterMut<'a,T>,
+    b: slice::IterMut<'a,T>,
+}
+impl<'a,T> Iterator for DequeIterMut<'a,T> {
+    type Item = &'a mut T;
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        match self.a.next() {
+            Some(t) => Some(t),
+            None => self.b.next(),
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.a.len()+self.b.len();
+        (n, Some(n))
+    }
+}
+impl<'a,T> DoubleEndedIterator for DequeIterMut<'a,T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        match self.b.next_back() {
+            Some(t) => Some(t),
+            None => self.a.next_back(),
+        }
+    }
+}
+impl<T> ExactSizeIterator for DequeIterMut<'_,T> {}
+impl<T> FusedIterator for DequeIterMut<'_,T> {}
+
+// An owning iterator.  Whatever isn't consumed gets dropped along with the deque.
+pub struct DequeIntoIter<T, const N:usize>(StackDeque<T,N>);
+impl<T, const N:usize> Iterator for DequeIntoIter<T,N> {
+    type Item = T;
+    #[inline]
+    fn next(&mut self) -> Option<T> { self.0.try_pop_front() }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.0.length, Some(self.0.length)) }
+}
+impl<T, const N:usize> DoubleEndedIterator for DequeIntoIter<T,N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> { self.0.try_pop_back() }
+}
+impl<T, const N:usize> ExactSizeIterator for DequeIntoIter<T,N> {}
+impl<T, const N:usize> FusedIterator for DequeIntoIter<T,N> {}
@@ -0,0 +1,336 @@
+use crate::{SVec, StackVec, StackedError};
+
+use std::fmt;
+use std::slice;
+use std::iter::{self, FusedIterator};
+use std::borrow::Borrow;
+use std::ops::{Index, RangeBounds, Bound};
+use std::hash::{Hash, Hasher};
+use std::cmp::Ordering;
+
+
+// A small ordered map.  The pairs live in a StackVec, sorted by key, so lookups are a binary search and iteration is in key order.
+// Insert/remove shift the pairs after the slot, which is cheap at the sizes this is meant for (a few dozen pairs).
+pub struct SMap<K, V, const N:usize> {
+    vec: StackVec<(K,V),N>,
+}
+
+impl<K, V, const N:usize> SMap<K,V,N> {
+    #[inline]
+    pub fn new() -> Self { Self{ vec:StackVec::new() } }
+    #[inline]
+    pub fn cap_of_type() -> usize { N }
+    #[inline]
+    pub fn cap(&self) -> usize { N }
+    #[inline]
+    pub fn len(&self) -> usize { self.vec.len() }
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.vec.is_empty() }
+    #[inline]
+    pub fn clear(&mut self) { self.vec.clear() }
+
+    // The pairs, in key order.  Only iter_mut()/values_mut() hand out '&mut', and only to values:  a changed key would end up in the wrong slot.
+    #[inline]
+    pub fn as_slice(&self) -> &[(K,V)] { self.vec.as_slice() }
+
+    #[inline]
+    pub fn iter(&self) -> MapIter<'_,K,V> { MapIter(self.vec.as_slice().iter()) }
+    #[inline]
+    pub fn iter_mut(&mut self) -> MapIterMut<'_,K,V> { MapIterMut(self.vec.as_mut_slice().iter_mut()) }
+    #[inline]
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item=&K> + ExactSizeIterator { self.vec.as_slice().iter().map(|(k,_)| k) }
+    #[inline]
+    pub fn values(&self) -> impl DoubleEndedIterator<Item=&V> + ExactSizeIterator { self.vec.as_slice().iter().map(|(_,v)| v) }
+    #[inline]
+    pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item=&mut V> + ExactSizeIterator { self.vec.as_mut_slice().iter_mut().map(|(_,v)| v) }
+
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(&K,&V)> { self.vec.first().map(|(k,v)| (k,v)) }
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(&K,&V)> { self.vec.last().map(|(k,v)| (k,v)) }
+    #[inline]
+    pub fn pop_first(&mut self) -> Option<(K,V)> { self.vec.try_remove(0) }
+    #[inline]
+    pub fn pop_last(&mut self) -> Option<(K,V)> { self.vec.try_pop() }
+
+    // Keeps the pairs where 'f' returns true.  The order is untouched, so the map stays sorted.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f:F) where F:FnMut(&K, &mut V)->bool {
+        self.vec.retain_mut(|(k,v)| f(k,v));
+    }
+}
+
+impl<K, V, const N:usize> SMap<K,V,N> where K:Ord {
+    // Ok(slot of the key), or Err(slot where it would go).
+    #[inline]
+    fn search<Q>(&self, key:&Q) -> Result<usize,usize> where K:Borrow<Q>, Q:Ord+?Sized {
+        self.vec.binary_search_by(|(k,_)| k.borrow().cmp(key))
+    }
+
+    #[inline]
+    pub fn get<Q>(&self, key:&Q) -> Option<&V> where K:Borrow<Q>, Q:Ord+?Sized {
+        let i = self.search(key).ok()?;
+        Some(&self.vec[i].1)
+    }
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key:&Q) -> Option<&mut V> where K:Borrow<Q>, Q:Ord+?Sized {
+        let i = self.search(key).ok()?;
+        Some(&mut self.vec[i].1)
+    }
+    #[inline]
+    pub fn get_key_value<Q>(&self, key:&Q) -> Option<(&K,&V)> where K:Borrow<Q>, Q:Ord+?Sized {
+        let i = self.search(key).ok()?;
+        let (k,v) = &self.vec[i];
+        Some((k,v))
+    }
+    #[inline]
+    pub fn contains_key<Q>(&self, key:&Q) -> bool where K:Borrow<Q>, Q:Ord+?Sized {
+        self.search(key).is_ok()
+    }
+
+    // Replacing the value of an existing key always works, and gives you the old value.
+    // Adding a new key to a full map gives you the pair back, just like SVec::push.
+    pub fn insert(&mut self, key:K, val:V) -> Result<Option<V>,((K,V),StackedError)> {
+        match self.search(&key) {
+            Ok(i) => Ok(Some(std::mem::replace(&mut self.vec[i].1, val))),
+            Err(i) => self.vec.try_insert(i, (key,val)).map(|_| None),
+        }
+    }
+    #[inline]
+    pub fn remove<Q>(&mut self, key:&Q) -> Option<V> where K:Borrow<Q>, Q:Ord+?Sized {
+        self.remove_entry(key).map(|(_,v)| v)
+    }
+    #[inline]
+    pub fn remove_entry<Q>(&mut self, key:&Q) -> Option<(K,V)> where K:Borrow<Q>, Q:Ord+?Sized {
+        let i = self.search(key).ok()?;
+        Some(self.vec.remove(i))
+    }
+
+    pub fn entry(&mut self, key:K) -> Entry<'_,K,V,N> {
+        match self.search(&key) {
+            Ok(i) => Entry::Occupied(OccupiedEntry{ map:self, i }),
+            Err(i) => Entry::Vacant(VacantEntry{ map:self, i, key }),
+        }
+    }
+
+    // The pairs with keys in 'range', in key order.  A backwards range (like 5..2) is just empty.
+    pub fn range<Q,R>(&self, range:R) -> MapIter<'_,K,V> where K:Borrow<Q>, Q:Ord+?Sized, R:RangeBounds<Q> {
+        let s = self.vec.as_slice();
+        let start = match range.start_bound() {
+            Bound::Included(q) => s.partition_point(|(k,_)| k.borrow()<q),
+            Bound::Excluded(q) => s.partition_point(|(k,_)| k.borrow()<=q),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(q) => s.partition_point(|(k,_)| k.borrow()<=q),
+            Bound::Excluded(q) => s.partition_point(|(k,_)| k.borrow()<q),
+            Bound::Unbounded => s.len(),
+        };
+        MapIter(s[start..end.max(start)].iter())
+    }
+}
+
+
+pub enum Entry<'a, K, V, const N:usize> {
+    Occupied(OccupiedEntry<'a,K,V,N>),
+    Vacant(VacantEntry<'a,K,V,N>),
+}
+pub struct OccupiedEntry<'a, K, V, const N:usize> {
+    map: &'a mut SMap<K,V,N>,
+    i: usize,
+}
+pub struct VacantEntry<'a, K, V, const N:usize> {
+    map: &'a mut SMap<K,V,N>,
+    i: usize,
+    key: K,
+}
+
+impl<'a, K, V, const N:usize> Entry<'a,K,V,N> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+    // These can overflow when the key is new, so (unlike BTreeMap) they return a Result.
+    #[inline]
+    pub fn or_insert(self, val:V) -> Result<&'a mut V,((K,V),StackedError)> {
+        self.or_insert_with(|| val)
+    }
+    #[inline]
+    pub fn or_insert_with<F>(self, f:F) -> Result<&'a mut V,((K,V),StackedError)> where F:FnOnce()->V {
+        match self {
+            Entry::Occupied(e) => Ok(e.into_mut()),
+            Entry::Vacant(e) => e.insert(f()),
+        }
+    }
+    #[inline]
+    pub fn or_default(self) -> Result<&'a mut V,((K,V),StackedError)> where V:Default {
+        self.or_insert_with(V::default)
+    }
+    #[inline]
+    pub fn and_modify<F>(mut self, f:F) -> Self where F:FnOnce(&mut V) {
+        if let Entry::Occupied(e) = &mut self { f(e.get_mut()); }
+        self
+    }
+}
+
+impl<'a, K, V, const N:usize> OccupiedEntry<'a,K,V,N> {
+    #[inline]
+    pub fn key(&self) -> &K { &self.map.vec[self.i].0 }
+    #[inline]
+    pub fn get(&self) -> &V { &self.map.vec[self.i].1 }
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V { &mut self.map.vec[self.i].1 }
+    #[inline]
+    pub fn into_mut(self) -> &'a mut V { &mut self.map.vec[self.i].1 }
+    #[inline]
+    pub fn insert(&mut self, val:V) -> V { std::mem::replace(self.get_mut(), val) }
+    #[inline]
+    pub fn remove(self) -> V { self.remove_entry().1 }
+    #[inline]
+    pub fn remove_entry(self) -> (K,V) { self.map.vec.remove(self.i) }
+}
+
+impl<'a, K, V, const N:usize> VacantEntry<'a,K,V,N> {
+    #[inline]
+    pub fn key(&self) -> &K { &self.key }
+    #[inline]
+    pub fn into_key(self) -> K { self.key }
+    #[inline]
+    pub fn insert(self, val:V) -> Result<&'a mut V,((K,V),StackedError)> {
+        self.map.vec.try_insert(self.i, (self.key,val))?;
+        Ok(&mut self.map.vec[self.i].1)
+    }
+}
+
+
+// Panics if the key is missing, like BTreeMap.
+impl<K, V, Q, const N:usize> Index<&Q> for SMap<K,V,N> where K:Ord+Borrow<Q>, Q:Ord+?Sized {
+    type Output = V;
+    #[inline]
+    fn index(&self, key:&Q) -> &V {
+        match self.get(key) {
+            Some(v) => v,
+            None => panic!("key not found in SMap"),
+        }
+    }
+}
+
+impl<K, V, const N:usize> IntoIterator for SMap<K,V,N> {
+    type Item = (K,V);
+    type IntoIter = crate::IntoIter<(K,V),N>;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter { self.vec.into_iter() }
+}
+impl<'a, K, V, const N:usize> IntoIterator for &'a SMap<K,V,N> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = MapIter<'a,K,V>;
+    #[inline]
+    fn into_iter(self) -> MapIter<'a,K,V> { self.iter() }
+}
+impl<'a, K, V, const N:usize> IntoIterator for &'a mut SMap<K,V,N> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = MapIterMut<'a,K,V>;
+    #[inline]
+    fn into_iter(self) -> MapIterMut<'a,K,V> { self.iter_mut() }
+}
+
+// Later pairs replace earlier ones with the same key, so only distinct keys count against N.  (Overflow panics; see StackVec's FromIterator.)
+impl<K, V, const N:usize> iter::FromIterator<(K,V)> for SMap<K,V,N> where K:Ord {
+    fn from_iter<I>(iter:I) -> Self where I:IntoIterator<Item=(K,V)> {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+impl<K, V, const N:usize> Extend<(K,V)> for SMap<K,V,N> where K:Ord {
+    fn extend<I>(&mut self, iter:I) where I:IntoIterator<Item=(K,V)> {
+        for (k,v) in iter {
+            if let Err((_,err)) = self.insert(k,v) { panic!("{}", err); }
+        }
+    }
+}
+
+// Equality, ordering and hashing all come from the sorted pairs, so maps of different capacities compare fine.
+impl<K, V, const N:usize, const M:usize> PartialEq<SMap<K,V,M>> for SMap<K,V,N> where K:PartialEq, V:PartialEq {
+    #[inline]
+    fn eq(&self, other:&SMap<K,V,M>) -> bool { self.vec.as_slice()==other.vec.as_slice() }
+}
+impl<K, V, const N:usize> Eq for SMap<K,V,N> where K:Eq, V:Eq {}
+impl<K, V, const N:usize, const M:usize> PartialOrd<SMap<K,V,M>> for SMap<K,V,N> where K:PartialOrd, V:PartialOrd {
+    #[inline]
+    fn partial_cmp(&self, other:&SMap<K,V,M>) -> Option<Ordering> { self.vec.as_slice().partial_cmp(other.vec.as_slice()) }
+}
+impl<K, V, const N:usize> Ord for SMap<K,V,N> where K:Ord, V:Ord {
+    #[inline]
+    fn cmp(&self, other:&Self) -> Ordering { self.vec.as_slice().cmp(other.vec.as_slice()) }
+}
+impl<K, V, const N:usize> Hash for SMap<K,V,N> where K:Hash, V:Hash {
+    #[inline]
+    fn hash<H>(&self, state:&mut H) where H:Hasher { self.vec.as_slice().hash(state) }
+}
+
+impl<K, V, const N:usize> Default for SMap<K,V,N> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<K, V, const N:usize> Clone for SMap<K,V,N> where K:Clone, V:Clone {
+    #[inline]
+    fn clone(&self) -> Self { Self{ vec:self.vec.clone() } }
+    #[inline]
+    fn clone_from(&mut self, source:&Self) { self.vec.clone_from(&source.vec) }
+}
+
+impl<K, V, const N:usize> fmt::Debug for SMap<K,V,N> where K:fmt::Debug, V:fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut nonempty = false;
+        write!(f, "SMap{}{{", N)?;
+        for (k,v) in self.iter() {
+            if nonempty { write!(f, ",")?; }
+            nonempty = true;
+            write!(f, " {:?}: {:?}", k, v)?;
+        }
+        if nonempty { write!(f, " ")?; }
+        write!(f, "}}")?;
+        Ok(())
+    }
+}
+
+
+// Thin wrappers around the slice iterators that split each pair into a key and a value.
+pub struct MapIter<'a,K,V>(slice::Iter<'a,(K,V)>);
+impl<'a,K,V> Iterator for MapIter<'a,K,V> {
+    type Item = (&'a K, &'a V);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> { self.0.next().map(|(k,v)| (k,v)) }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+impl<K,V> DoubleEndedIterator for MapIter<'_,K,V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> { self.0.next_back().map(|(k,v)| (k,v)) }
+}
+impl<K,V> ExactSizeIterator for MapIter<'_,K,V> {}
+impl<K,V> FusedIterator for MapIter<'_,K,V> {}
+impl<K,V> Clone for MapIter<'_,K,V> {
+    #[inline]
+    fn clone(&self) -> Self { MapIter(self.0.clone()) }
+}
+
+// Keys stay read-only, so the map can't get out of order.
+pub struct MapIterMut<'a,K,V>(slice::IterMut<'a,(K,V)>);
+impl<'a,K,V> Iterator for MapIterMut<'a,K,V> {
+    type Item = (&'a K, &'a mut V);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> { self.0.next().map(|(k,v)| (&*k,v)) }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+impl<K,V> DoubleEndedIterator for MapIterMut<'_,K,V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> { self.0.next_back().map(|(k,v)| (&*k,v)) }
+}
+impl<K,V> ExactSizeIterator for MapIterMut<'_,K,V> {}
+impl<K,V> FusedIterator for MapIterMut<'_,K,V> {}
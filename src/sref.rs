@@ -1,6 +1,139 @@
+use crate::{SVec, StackedError};
 
-//pub struct SRef<T> {
-//    svec: &SVec,
-//    i: usize,
-//}
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Deref, Index, IndexMut};
 
+
+// Raw indexes are still the simplest (and cheapest) way to refer to items, as long as you stick to the Append-Only
+// Interface.  Once you start using insert/remove/etc, an old index can silently point at the wrong item.
+//
+// If you want protection against that, wrap your SVec in a Tracked.  It keeps a generation counter that gets bumped by
+// every structural mutation, and each SRef remembers the generation it was made in.  Looking up a stale SRef gives you
+// None instead of the wrong item.  Appending doesn't bump the generation (existing items don't move), so that path stays free.
+//
+// If you only ever append while you hold your indexes, StackVec::brand() (in brand.rs) is cheaper:  no counter, and no bounds checks.
+
+// A handle to one item in a Tracked SVec.
+pub struct SRef<T> {
+    i: usize,
+    generation: u64,
+    _item: PhantomData<fn() -> T>,  // Just for type-checking.  An SRef doesn't own or borrow a T.
+}
+impl<T> SRef<T> {
+    #[inline]
+    pub fn index(&self) -> usize { self.i }
+    #[inline]
+    pub fn generation(&self) -> u64 { self.generation }
+}
+// (Derives would require T:Clone, T:PartialEq, etc, which we don't need.)
+impl<T> Clone for SRef<T> {
+    #[inline]
+    fn clone(&self) -> Self { *self }
+}
+impl<T> Copy for SRef<T> {}
+impl<T> PartialEq for SRef<T> {
+    #[inline]
+    fn eq(&self, other:&Self) -> bool { self.i==other.i && self.generation==other.generation }
+}
+impl<T> Eq for SRef<T> {}
+impl<T> Hash for SRef<T> {
+    fn hash<H>(&self, state:&mut H) where H:Hasher { self.i.hash(state); self.generation.hash(state); }
+}
+impl<T> fmt::Debug for SRef<T> {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "SRef({}@{})", self.i, self.generation)
+    }
+}
+
+// An SVec plus a generation counter.  Reading goes through Deref;  changing the structure goes through mutate().
+pub struct Tracked<V> {
+    svec: V,
+    generation: u64,
+}
+impl<V> Tracked<V> where V:SVec {
+    #[inline]
+    pub fn new() -> Self { Self::from(V::new()) }
+    #[inline]
+    pub fn into_inner(self) -> V { self.svec }
+    #[inline]
+    pub fn generation(&self) -> u64 { self.generation }
+
+    // ---- Append-Only Interface ----
+    // None of these invalidate existing SRefs.
+    #[inline]
+    pub fn push(&mut self, t:V::Item) -> Result<usize,(V::Item,StackedError)> {
+        self.svec.push(t)
+    }
+    #[inline]
+    pub fn push_ref(&mut self, t:V::Item) -> Result<SRef<V::Item>,(V::Item,StackedError)> {
+        let i = self.svec.push(t)?;
+        Ok(SRef{ i, generation:self.generation, _item:PhantomData })
+    }
+    // Makes an SRef for an item that's already there.
+    #[inline]
+    pub fn sref(&self, i:usize) -> Option<SRef<V::Item>> {
+        if i>=self.svec.len() { return None }
+        Some(SRef{ i, generation:self.generation, _item:PhantomData })
+    }
+    #[inline]
+    pub fn is_valid(&self, r:SRef<V::Item>) -> bool {
+        r.generation==self.generation && r.i<self.svec.len()
+    }
+    #[inline]
+    pub fn get(&self, r:SRef<V::Item>) -> Option<&V::Item> {
+        if r.generation!=self.generation { return None }
+        self.svec.get(r.i)
+    }
+    // Changing an item in place doesn't move anything, so this doesn't bump the generation.
+    #[inline]
+    pub fn get_mut(&mut self, r:SRef<V::Item>) -> Option<&mut V::Item> {
+        if r.generation!=self.generation { return None }
+        self.svec.get_mut(r.i)
+    }
+
+    // ---- Mutation Interface ----
+    // Gives you the whole SVec to insert/remove/sort/etc.  This invalidates every SRef made so far.
+    #[inline]
+    pub fn mutate(&mut self) -> &mut V {
+        self.generation = self.generation.wrapping_add(1);
+        &mut self.svec
+    }
+}
+
+impl<V> From<V> for Tracked<V> where V:SVec {
+    #[inline]
+    fn from(svec:V) -> Self { Self{ svec, generation:0 } }
+}
+impl<V> Default for Tracked<V> where V:SVec {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<V> Deref for Tracked<V> {
+    type Target = V;
+    #[inline]
+    fn deref(&self) -> &V { &self.svec }
+}
+
+// Panics on a stale SRef, just like indexing with a bad usize.
+impl<V> Index<SRef<V::Item>> for Tracked<V> where V:SVec {
+    type Output = V::Item;
+    #[inline]
+    fn index(&self, r:SRef<V::Item>) -> &V::Item {
+        match self.get(r) {
+            Some(t) => t,
+            None => panic!("stale {:?} (generation is now {})", r, self.generation),
+        }
+    }
+}
+impl<V> IndexMut<SRef<V::Item>> for Tracked<V> where V:SVec {
+    #[inline]
+    fn index_mut(&mut self, r:SRef<V::Item>) -> &mut V::Item {
+        let generation = self.generation;
+        match self.get_mut(r) {
+            Some(t) => t,
+            None => panic!("stale {:?} (generation is now {})", r, generation),
+        }
+    }
+}
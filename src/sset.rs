@@ -0,0 +1,337 @@
+use crate::{SVec, StackVec, StackedError, Iter, IntoIter};
+
+use std::fmt;
+use std::iter::{self, FusedIterator, Peekable};
+use std::slice;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::{RangeBounds, Bound};
+use std::hash::{Hash, Hasher};
+
+
+// A small ordered set.  Same idea as SMap:  the items live in a StackVec, sorted and deduplicated, so lookups are a binary search.
+// The set algebra walks both sorted slices in step, so it's linear and never needs to sort anything.
+pub struct SSet<T, const N:usize> {
+    vec: StackVec<T,N>,
+}
+
+impl<T, const N:usize> SSet<T,N> {
+    #[inline]
+    pub fn new() -> Self { Self{ vec:StackVec::new() } }
+    #[inline]
+    pub fn cap_of_type() -> usize { N }
+    #[inline]
+    pub fn cap(&self) -> usize { N }
+    #[inline]
+    pub fn len(&self) -> usize { self.vec.len() }
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.vec.is_empty() }
+    #[inline]
+    pub fn clear(&mut self) { self.vec.clear() }
+
+    // The items, in order.  Read-only, for the same reason as SMap::as_slice().
+    #[inline]
+    pub fn as_slice(&self) -> &[T] { self.vec.as_slice() }
+    #[inline]
+    pub fn iter(&self) -> Iter<'_,T> { self.vec.iter() }
+
+    #[inline]
+    pub fn first(&self) -> Option<&T> { self.vec.first() }
+    #[inline]
+    pub fn last(&self) -> Option<&T> { self.vec.last() }
+    #[inline]
+    pub fn pop_first(&mut self) -> Option<T> { self.vec.try_remove(0) }
+    #[inline]
+    pub fn pop_last(&mut self) -> Option<T> { self.vec.try_pop() }
+
+    #[inline]
+    pub fn retain<F>(&mut self, f:F) where F:FnMut(&T)->bool {
+        self.vec.retain(f);
+    }
+}
+
+impl<T, const N:usize> SSet<T,N> where T:Ord {
+    #[inline]
+    fn search<Q>(&self, q:&Q) -> Result<usize,usize> where T:Borrow<Q>, Q:Ord+?Sized {
+        self.vec.binary_search_by(|t| t.borrow().cmp(q))
+    }
+
+    // Like StackVec::try_from_iter.  Duplicates are fine;  it only fails if there are more than N distinct items.
+    pub fn try_from_iter<I>(iter:I) -> Result<Self,StackedError> where I:IntoIterator<Item=T> {
+        let mut out = Self::new();
+        for t in iter { out.insert(t)?; }
+        Ok(out)
+    }
+
+    #[inline]
+    pub fn contains<Q>(&self, q:&Q) -> bool where T:Borrow<Q>, Q:Ord+?Sized {
+        self.search(q).is_ok()
+    }
+    #[inline]
+    pub fn get<Q>(&self, q:&Q) -> Option<&T> where T:Borrow<Q>, Q:Ord+?Sized {
+        let i = self.search(q).ok()?;
+        Some(&self.vec[i])
+    }
+    // Ok(true) if 't' was added, Ok(false) if it was already there (and 't' is dropped, like BTreeSet).
+    // Adding a new item to a full set gives you the item back, just like SVec::push.
+    pub fn insert(&mut self, t:T) -> Result<bool,(T,StackedError)> {
+        match self.search(&t) {
+            Ok(_) => Ok(false),
+            Err(i) => self.vec.try_insert(i, t).map(|_| true),
+        }
+    }
+    #[inline]
+    pub fn remove<Q>(&mut self, q:&Q) -> bool where T:Borrow<Q>, Q:Ord+?Sized {
+        self.take(q).is_some()
+    }
+    #[inline]
+    pub fn take<Q>(&mut self, q:&Q) -> Option<T> where T:Borrow<Q>, Q:Ord+?Sized {
+        let i = self.search(q).ok()?;
+        Some(self.vec.remove(i))
+    }
+
+    // The items in 'range', in order.  A backwards range (like 5..2) is just empty.
+    pub fn range<Q,R>(&self, range:R) -> Iter<'_,T> where T:Borrow<Q>, Q:Ord+?Sized, R:RangeBounds<Q> {
+        let s = self.vec.as_slice();
+        let start = match range.start_bound() {
+            Bound::Included(q) => s.partition_point(|t| t.borrow()<q),
+            Bound::Excluded(q) => s.partition_point(|t| t.borrow()<=q),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(q) => s.partition_point(|t| t.borrow()<=q),
+            Bound::Excluded(q) => s.partition_point(|t| t.borrow()<q),
+            Bound::Unbounded => s.len(),
+        };
+        Iter(s[start..end.max(start)].iter())
+    }
+
+    // ---- Set Algebra ----
+    // These are lazy, and 'other' can have a different capacity.  Use collect_set() on the result to build a new SSet.
+    #[inline]
+    pub fn union<'a, const M:usize>(&'a self, other:&'a SSet<T,M>) -> Union<'a,T> {
+        Union(Merge::new(self.as_slice(), other.as_slice()))
+    }
+    #[inline]
+    pub fn intersection<'a, const M:usize>(&'a self, other:&'a SSet<T,M>) -> Intersection<'a,T> {
+        Intersection(Merge::new(self.as_slice(), other.as_slice()))
+    }
+    #[inline]
+    pub fn difference<'a, const M:usize>(&'a self, other:&'a SSet<T,M>) -> Difference<'a,T> {
+        Difference(Merge::new(self.as_slice(), other.as_slice()))
+    }
+    #[inline]
+    pub fn symmetric_difference<'a, const M:usize>(&'a self, other:&'a SSet<T,M>) -> SymmetricDifference<'a,T> {
+        SymmetricDifference(Merge::new(self.as_slice(), other.as_slice()))
+    }
+
+    #[inline]
+    pub fn is_subset<const M:usize>(&self, other:&SSet<T,M>) -> bool {
+        self.len()<=other.len() && self.difference(other).next().is_none()
+    }
+    #[inline]
+    pub fn is_superset<const M:usize>(&self, other:&SSet<T,M>) -> bool { other.is_subset(self) }
+    #[inline]
+    pub fn is_disjoint<const M:usize>(&self, other:&SSet<T,M>) -> bool {
+        self.intersection(other).next().is_none()
+    }
+}
+
+
+// Steps through two sorted slices together.  Each call tells you which side has the smaller item (or both, if they're equal).
+struct Merge<'a,T> {
+    a: Peekable<slice::Iter<'a,T>>,
+    b: Peekable<slice::Iter<'a,T>>,
+}
+enum Side<'a,T> { A(&'a T), B(&'a T), Both(&'a T) }
+impl<'a,T> Merge<'a,T> where T:Ord {
+    #[inline]
+    fn new(a:&'a [T], b:&'a [T]) -> Self { Self{ a:a.iter().peekable(), b:b.iter().peekable() } }
+    #[inline]
+    fn next(&mut self) -> Option<Side<'a,T>> {
+        let ord = match (self.a.peek(), self.b.peek()) {
+            (None, None) => return None,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(a), Some(b)) => a.cmp(b),
+        };
+        Some(match ord {
+            Ordering::Less => Side::A(self.a.next()?),
+            Ordering::Greater => Side::B(self.b.next()?),
+            Ordering::Equal => { self.b.next(); Side::Both(self.a.next()?) }
+        })
+    }
+    #[inline]
+    fn lens(&self) -> (usize, usize) { (self.a.len(), self.b.len()) }
+}
+impl<T> Clone for Merge<'_,T> {
+    #[inline]
+    fn clone(&self) -> Self { Self{ a:self.a.clone(), b:self.b.clone() } }
+}
+
+// Items in either set.
+pub struct Union<'a,T>(Merge<'a,T>);
+// Items in both sets.
+pub struct Intersection<'a,T>(Merge<'a,T>);
+// Items in the first set but not the second.
+pub struct Difference<'a,T>(Merge<'a,T>);
+// Items in exactly one of the sets.
+pub struct SymmetricDifference<'a,T>(Merge<'a,T>);
+
+impl<'a,T> Iterator for Union<'a,T> where T:Ord {
+    type Item = &'a T;
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        match self.0.next()? { Side::A(t) | Side::B(t) | Side::Both(t) => Some(t) }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a,b) = self.0.lens();
+        (a.max(b), Some(a+b))
+    }
+}
+impl<'a,T> Iterator for Intersection<'a,T> where T:Ord {
+    type Item = &'a T;
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        loop { if let Side::Both(t) = self.0.next()? { return Some(t) } }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a,b) = self.0.lens();
+        (0, Some(a.min(b)))
+    }
+}
+impl<'a,T> Iterator for Difference<'a,T> where T:Ord {
+    type Item = &'a T;
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            match self.0.next()? {
+                Side::A(t) => return Some(t),
+                Side::B(_) => { self.0.a.peek()?; }  // Stop early once there's nothing left on our side.
+                Side::Both(_) => {}
+            }
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a,b) = self.0.lens();
+        (a.saturating_sub(b), Some(a))
+    }
+}
+impl<'a,T> Iterator for SymmetricDifference<'a,T> where T:Ord {
+    type Item = &'a T;
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            match self.0.next()? {
+                Side::A(t) | Side::B(t) => return Some(t),
+                Side::Both(_) => {}
+            }
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a,b) = self.0.lens();
+        (0, Some(a+b))
+    }
+}
+
+macro_rules! impl_set_op {
+    ( $($op:ident),* ) => { $(
+        impl<'a,T> $op<'a,T> where T:Ord+Clone {
+            // Builds a new SSet out of the results.  The results are already sorted and distinct, so this is just a series of pushes.
+            // On overflow, 'rejected' counts every item that didn't fit.
+            pub fn collect_set<const K:usize>(mut self) -> Result<SSet<T,K>,StackedError> {
+                let mut out = SSet::new();
+                while let Some(t) = self.next() {
+                    if out.vec.push(t.clone()).is_err() {
+                        return Err(StackedError::Overflow{ cap:K, rejected:1+self.count() });
+                    }
+                }
+                Ok(out)
+            }
+        }
+        impl<T> FusedIterator for $op<'_,T> where T:Ord {}
+        impl<T> Clone for $op<'_,T> {
+            #[inline]
+            fn clone(&self) -> Self { $op(self.0.clone()) }
+        }
+    )* }
+}
+impl_set_op!(Union, Intersection, Difference, SymmetricDifference);
+
+
+impl<T, const N:usize> IntoIterator for SSet<T,N> {
+    type Item = T;
+    type IntoIter = IntoIter<T,N>;
+    #[inline]
+    fn into_iter(self) -> IntoIter<T,N> { self.vec.into_iter() }
+}
+impl<'a, T, const N:usize> IntoIterator for &'a SSet<T,N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a,T>;
+    #[inline]
+    fn into_iter(self) -> Iter<'a,T> { self.iter() }
+}
+
+// Duplicates are skipped, so only distinct items count against N.  (Overflow panics; see StackVec's FromIterator.)
+impl<T, const N:usize> iter::FromIterator<T> for SSet<T,N> where T:Ord {
+    fn from_iter<I>(iter:I) -> Self where I:IntoIterator<Item=T> {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+impl<T, const N:usize> Extend<T> for SSet<T,N> where T:Ord {
+    fn extend<I>(&mut self, iter:I) where I:IntoIterator<Item=T> {
+        for t in iter {
+            if let Err((_,err)) = self.insert(t) { panic!("{}", err); }
+        }
+    }
+}
+
+impl<T, const N:usize, const M:usize> PartialEq<SSet<T,M>> for SSet<T,N> where T:PartialEq {
+    #[inline]
+    fn eq(&self, other:&SSet<T,M>) -> bool { self.vec.as_slice()==other.vec.as_slice() }
+}
+impl<T, const N:usize> Eq for SSet<T,N> where T:Eq {}
+impl<T, const N:usize> PartialOrd for SSet<T,N> where T:PartialOrd {
+    #[inline]
+    fn partial_cmp(&self, other:&Self) -> Option<Ordering> { self.vec.as_slice().partial_cmp(other.vec.as_slice()) }
+}
+impl<T, const N:usize> Ord for SSet<T,N> where T:Ord {
+    #[inline]
+    fn cmp(&self, other:&Self) -> Ordering { self.vec.as_slice().cmp(other.vec.as_slice()) }
+}
+impl<T, const N:usize> Hash for SSet<T,N> where T:Hash {
+    #[inline]
+    fn hash<H>(&self, state:&mut H) where H:Hasher { self.vec.as_slice().hash(state) }
+}
+
+impl<T, const N:usize> Default for SSet<T,N> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<T, const N:usize> Clone for SSet<T,N> where T:Clone {
+    #[inline]
+    fn clone(&self) -> Self { Self{ vec:self.vec.clone() } }
+    #[inline]
+    fn clone_from(&mut self, source:&Self) { self.vec.clone_from(&source.vec) }
+}
+
+impl<T, const N:usize> fmt::Debug for SSet<T,N> where T:fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut nonempty = false;
+        write!(f, "SSet{}{{", N)?;
+        for t in self.iter() {
+            if nonempty { write!(f, ",")?; }
+            nonempty = true;
+            write!(f, " {:?}", t)?;
+        }
+        if nonempty { write!(f, " ")?; }
+        write!(f, "}}")?;
+        Ok(())
+    }
+}
@@ -1,11 +1,27 @@
-use kerr::KErr;
-use std::ops::{Index, IndexMut};
+use crate::{StackedError, ExtendError};
+
+use std::fmt;
+use std::ptr;
+use std::slice::{self, SliceIndex};
+use std::mem::{MaybeUninit, ManuallyDrop};
+use std::ops::{Index, IndexMut, Deref, DerefMut, RangeBounds, Bound};
+use std::borrow::{Borrow, BorrowMut, Cow};
+use std::str::{self, Utf8Error};
+use std::iter::{self, FusedIterator};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 
 
 // For future reference: This is how you can manually convert a value to a type, and call a type-parameterized function:
 // pub fn cap<T>(_:&T) -> usize where T:SVec { T::cap() }
 
-pub trait SVec : Index<usize, Output=<Self as SVec>::Item> + IndexMut<usize> {  // Must use that crazy syntax to tell the compiler that the associated types are equal.
+// What try_extend() hands back on overflow:  the item that didn't fit, followed by the rest of the iterator.
+pub type Leftover<T,I> = iter::Chain<iter::Once<T>,I>;
+
+// Every SVec derefs to a plain slice, so the whole slice toolbox (sort, binary_search, chunks, contains, ...) works on it too.
+pub trait SVec : Index<usize, Output=<Self as SVec>::Item> + IndexMut<usize>  // Must use that crazy syntax to tell the compiler that the associated types are equal.
+               + Deref<Target=[<Self as SVec>::Item]> + DerefMut {
     type Item;
 
     // ---- Append-Only Interface ----
@@ -13,338 +29,882 @@ pub trait SVec : Index<usize, Output=<Self as SVec>::Item> + IndexMut<usize> {
     fn new() -> Self where Self:Sized;  // https://github.com/rust-lang/rfcs/blob/master/text/0546-Self-not-sized-by-default.md
     fn cap(&self) -> usize;
     fn len(&self) -> usize;
-    fn push(&mut self, t:Self::Item) -> Result<usize,KErr>;
-    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item=&'a Self::Item> + 'a>;
-    fn iter_owned<'a>(&'a mut self) -> Box<dyn Iterator<Item=Self::Item> + 'a>;
+    fn is_empty(&self) -> bool;
+    fn push(&mut self, t:Self::Item) -> Result<usize,(Self::Item,StackedError)>;  // On overflow, you get the item back.
+    #[allow(clippy::type_complexity)]
+    fn try_extend<I>(&mut self, iter:I) -> Result<usize,ExtendError<Leftover<Self::Item,I::IntoIter>>> where I:IntoIterator<Item=Self::Item>, Self:Sized;
+    fn extend_from_slice<'s>(&mut self, items:&'s [Self::Item]) -> Result<(),ExtendError<&'s [Self::Item]>> where Self::Item:Clone;
+    fn as_slice(&self) -> &[Self::Item];
+    fn get(&self, i:usize) -> Option<&Self::Item>;
+    fn first(&self) -> Option<&Self::Item>;
+    fn last(&self) -> Option<&Self::Item>;
+    fn iter(&self) -> Iter<'_,Self::Item>;
+    fn iter_owned(&mut self) -> IterOwned<'_,Self::Item>;
     
 
     // ---- Mutation Interface ----
     // If you use any of this section AT ALL, it is up to you to keep the bugs out.
+    // The plain versions panic on underflow/out-of-bounds/overflow.  The try_ versions never panic.
     fn clear(&mut self);
     fn pop(&mut self) -> Self::Item;
+    fn try_pop(&mut self) -> Option<Self::Item>;
     fn insert(&mut self, i:usize, t:Self::Item);
+    fn try_insert(&mut self, i:usize, t:Self::Item) -> Result<(),(Self::Item,StackedError)>;
     fn remove(&mut self, i:usize) -> Self::Item;
+    fn try_remove(&mut self, i:usize) -> Option<Self::Item>;
+    fn swap_remove(&mut self, i:usize) -> Self::Item;
+    fn try_swap_remove(&mut self, i:usize) -> Option<Self::Item>;
+    fn truncate(&mut self, len:usize);
+    fn resize(&mut self, len:usize, t:Self::Item) -> Result<(),StackedError> where Self::Item:Clone;
+    fn resize_with<F>(&mut self, len:usize, f:F) -> Result<(),StackedError> where F:FnMut()->Self::Item, Self:Sized;
+    fn split_off(&mut self, at:usize) -> Self where Self:Sized;
     fn reverse(&mut self);
-    fn iter_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item=&'a mut Self::Item> + 'a>;
+    fn drain<R>(&mut self, range:R) -> Drain<'_,Self::Item> where R:RangeBounds<usize>, Self:Sized;
+    fn retain<F>(&mut self, f:F) where F:FnMut(&Self::Item)->bool, Self:Sized;
+    fn retain_mut<F>(&mut self, f:F) where F:FnMut(&mut Self::Item)->bool, Self:Sized;
+    fn dedup(&mut self) where Self::Item:PartialEq;
+    fn dedup_by<F>(&mut self, same_bucket:F) where F:FnMut(&mut Self::Item, &mut Self::Item)->bool, Self:Sized;
+    fn dedup_by_key<K,F>(&mut self, key:F) where F:FnMut(&mut Self::Item)->K, K:PartialEq, Self:Sized;
+    fn get_mut(&mut self, i:usize) -> Option<&mut Self::Item>;
+    fn as_mut_slice(&mut self) -> &mut [Self::Item];
+    fn iter_mut(&mut self) -> IterMut<'_,Self::Item>;
 
     // ---- Internals ----
-    fn as_opt_slice(&self) -> &[Option<Self::Item>];
-    fn as_opt_slice_mut(&mut self) -> &mut[Option<Self::Item>];
+    // Pointers to the start of the storage.  Only the first len() items are initialized.
+    fn as_ptr(&self) -> *const Self::Item;
+    fn as_mut_ptr(&mut self) -> *mut Self::Item;
 }
 
 
 
-macro_rules! def_stackvec {
-    ( $size:expr, $svec:ident, $sstring:ident ) => {
-        pub struct $svec<T> {
-            data: [Option<T>; $size],
-            length: usize,
-        }
-        impl<T> SVec for $svec<T> {
-            type Item = T;
-
-            #[inline]
-            fn new() -> Self {
-                Self{ data:/*unsafe { mem::zeroed() },*/ [None; $size],  // const_in_array_repeat_expression
-                      length:0 }
-            }
-
-            #[inline]
-            fn cap(&self) -> usize { $size }
-            #[inline]
-            fn len(&self) -> usize { self.length }
-
-            fn clear(&mut self) {
-                while self.length>0 {
-                    self.data[self.length-1] = None;
-                    self.length-=1;
-                }
-            }
-
-            fn push(&mut self, t:T) -> Result<usize,KErr> {
-                let i = self.length;
-                if i>=Self::cap_of_type() { return Err(KErr::new("overflow")); }
-                self.data[i] = Some(t);
-                self.length+=1;
-                Ok(i)
-            }
-            fn pop(&mut self) -> T {
-                if self.length==0 { panic!("underflow"); }
-                let t = self.data[self.length-1].take().unwrap();
-                self.length-=1;
-                t
-            }
-
-            fn insert(&mut self, i:usize, t:T) {
-                if i>self.length { panic!("out-of-bounds"); }
-                if i>=Self::cap_of_type() { panic!("overflow"); }
+// 'data' is uninitialized memory.  Only the first 'length' slots hold live values; everything past that is garbage.
+// This lets push/pop be a plain write/read plus a length change, and we don't pay for an Option discriminant per slot.
+pub struct StackVec<T, const N:usize> {
+    pub(crate) data: [MaybeUninit<T>; N],
+    pub(crate) length: usize,
+}
+impl<T, const N:usize> SVec for StackVec<T,N> {
+    type Item = T;
 
-                unsafe {
-                    let p = &mut self.data[i] as *mut Option<T>;
-                    ptr::copy(p, p.offset(1), self.length-i);
-                    ptr::write(p, Some(t));
-                }
-                self.length+=1;
-            }
-            fn remove(&mut self, i:usize) -> T {
-                if i>=self.length { panic!("out-of-bounds"); }
-
-                let t = self.data[i].take().unwrap();
-                unsafe {
-                    let p = &mut self.data[i] as *mut Option<T>;
-                    self.length-=1;
-                    ptr::copy(p.offset(1), p, self.length-i);       // Already subtracted 1 from length.
-                    ptr::write(&mut self.data[self.length], None);  // Prevent double-drop.
-                }
-                t
-            }
+    #[inline]
+    fn new() -> Self {
+        Self{ data:[const { MaybeUninit::uninit() }; N],
+              length:0 }
+    }
 
-            fn reverse(&mut self) {
-                let mut i=0; let mut j=self.length-1;
-                let aptr = &mut self.data as *mut [Option<T>; $size];
-                while i<j {
-                    unsafe {
-                        // Cannot deref aptr outside the loop because of borrow checker.
-                        let i_p = &mut (*aptr)[i];
-                        let j_p = &mut (*aptr)[j];
-                        mem::swap(i_p, j_p);
-                    }
-
-                    i+=1; j-=1;
-                }
-            }
+    #[inline]
+    fn cap(&self) -> usize { N }
+    #[inline]
+    fn len(&self) -> usize { self.length }
+    #[inline]
+    fn is_empty(&self) -> bool { self.length==0 }
+
+    #[inline]
+    fn clear(&mut self) {
+        let len = self.length;
+        self.length = 0;  // Set this first, so a panicking Drop can't cause a double-drop.
+        // For types without drop glue (like u8), this compiles down to nothing:
+        unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.as_mut_ptr(), len)); }
+    }
 
-            #[inline]
-            fn as_opt_slice(&self) -> &[Option<T>] {
-                &self.data[..self.length]
-            }
-            #[inline]
-            fn as_opt_slice_mut(&mut self) -> &mut [Option<T>] {
-                &mut self.data[..self.length]
+    #[inline]
+    fn push(&mut self, t:T) -> Result<usize,(T,StackedError)> {
+        let i = self.length;
+        if i>=N { return Err((t, StackedError::Overflow{ cap:N, rejected:1 })); }
+        unsafe { ptr::write(self.as_mut_ptr().add(i), t); }
+        self.length+=1;
+        Ok(i)
+    }
+    // Appends items until the iterator runs dry, and returns how many were appended.
+    // If we fill up first, you get back an iterator that yields the item that didn't fit, followed by the rest.
+    fn try_extend<I>(&mut self, iter:I) -> Result<usize,ExtendError<Leftover<T,I::IntoIter>>> where I:IntoIterator<Item=T> {
+        let mut iter = iter.into_iter();
+        let mut accepted = 0;
+        while let Some(t) = iter.next() {
+            if let Err((t,_)) = self.push(t) {
+                let rejected = 1+iter.size_hint().0;  // (Might be more than this, but we don't consume the iterator to find out.)
+                return Err(ExtendError{ accepted,
+                                        leftover:iter::once(t).chain(iter),
+                                        error:StackedError::Overflow{ cap:N, rejected } });
             }
+            accepted+=1;
+        }
+        Ok(accepted)
+    }
+    // Clones as many items as will fit.  On overflow, 'leftover' is the part of 'items' that didn't fit.
+    fn extend_from_slice<'s>(&mut self, items:&'s [T]) -> Result<(),ExtendError<&'s [T]>> where T:Clone {
+        let room = N-self.length;
+        let (fits, rest) = items.split_at(room.min(items.len()));
+        for t in fits {
+            unsafe { ptr::write(self.as_mut_ptr().add(self.length), t.clone()); }
+            self.length+=1;  // Bump as we go, so a panicking clone() can't leak or double-drop.
+        }
+        if rest.is_empty() { return Ok(()) }
+        Err(ExtendError{ accepted:fits.len(),
+                         leftover:rest,
+                         error:StackedError::Overflow{ cap:N, rejected:rest.len() } })
+    }
 
-            fn iter<'a>(&'a self) -> Box<dyn Iterator<Item=&'a T> + 'a> {
-                Box::new(self.as_opt_slice().iter().map(|optref| optref.as_ref().unwrap()))
-            }
-            fn iter_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item=&'a mut T> + 'a> {
-                Box::new(self.as_opt_slice_mut().iter_mut().map(|optref| optref.as_mut().unwrap()))
-            }
-            // Due to our stack allocation and aversion to copying of data, I can't do a standard implementation of IntoIterator because it takes ownership.
-            // Here is a similar thing that takes '&mut' instead and returns owned objects.
-            fn iter_owned<'a>(&'a mut self) -> Box<dyn Iterator<Item=T> + 'a> {
-                Box::new(IntoIter::new(self))
-            }
+    #[inline]
+    fn pop(&mut self) -> T {
+        match self.try_pop() {
+            Some(t) => t,
+            None => panic!("{}", StackedError::Underflow),
+        }
+    }
+    #[inline]
+    fn try_pop(&mut self) -> Option<T> {
+        if self.length==0 { return None }
+        self.length-=1;
+        Some(unsafe { ptr::read(self.as_ptr().add(self.length)) })
+    }
 
+    fn insert(&mut self, i:usize, t:T) {
+        if let Err((_,err)) = self.try_insert(i,t) { panic!("{}", err); }
+    }
+    fn try_insert(&mut self, i:usize, t:T) -> Result<(),(T,StackedError)> {
+        if i>self.length { return Err((t, StackedError::OutOfBounds{ index:i, len:self.length })); }
+        if self.length>=N { return Err((t, StackedError::Overflow{ cap:N, rejected:1 })); }
+
+        unsafe {
+            let p = self.as_mut_ptr().add(i);
+            ptr::copy(p, p.add(1), self.length-i);
+            ptr::write(p, t);
         }
-        impl<T> $svec<T> {
-            #[inline]
-            pub fn cap_of_type() -> usize { $size }
+        self.length+=1;
+        Ok(())
+    }
+    fn remove(&mut self, i:usize) -> T {
+        match self.try_remove(i) {
+            Some(t) => t,
+            None => panic!("{}", StackedError::OutOfBounds{ index:i, len:self.length }),
+        }
+    }
+    fn try_remove(&mut self, i:usize) -> Option<T> {
+        if i>=self.length { return None }
+
+        unsafe {
+            let p = self.as_mut_ptr().add(i);
+            let t = ptr::read(p);
+            self.length-=1;
+            ptr::copy(p.add(1), p, self.length-i);  // Already subtracted 1 from length.
+            Some(t)
+        }
+    }
 
-            // I can't put this in the trait interface because I don't have a way of specifying $svec.
-            // I can refactor when we have const_generics.
-            #[inline]
-            pub fn new_of<U>(&self) -> $svec<U> { $svec::<U>::new() }
-
-            // I'm not able to implement the TryFrom trait because of a conflict with a blanket impl.
-            //     impl<T,I> TryFrom<I> for $svec<T> where I:IntoIterator<Item=T>
-            // So that's why I'm putting this here:
-            pub fn try_from_iter<I>(iter:I) -> Result<Self,KErr> where I:IntoIterator<Item=T> {
-                let mut out = Self::new();
-                for t in iter { out.push(t)?; }
-                Ok(out)
-                
+    // Removes the items in 'range' and yields them (in order).  The tail gets shifted down once, when the Drain is dropped.
+    // If the Drain is leaked (mem::forget), the SVec just ends up truncated to range.start -- no double-drops.
+    fn drain<R>(&mut self, range:R) -> Drain<'_,T> where R:RangeBounds<usize> {
+        let len = self.length;
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i.checked_add(1).unwrap_or_else(|| panic!("{}", StackedError::OutOfBounds{ index:i, len })),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i.checked_add(1).unwrap_or_else(|| panic!("{}", StackedError::OutOfBounds{ index:i, len })),
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => len,
+        };
+        if end>len { panic!("{}", StackedError::OutOfBounds{ index:end, len }); }
+        if start>end { panic!("{}", StackedError::OutOfBounds{ index:start, len:end }); }
+
+        self.length = start;
+        Drain{ data:self.as_mut_ptr(),
+               length:&mut self.length,
+               start,
+               next:start,
+               end,
+               tail_start:end,
+               tail_len:len-end,
+               _items:PhantomData }
+    }
+
+    // All of these compact the storage in a single pass, and drop each removed item exactly once (even if 'f' panics).
+    #[inline]
+    fn retain<F>(&mut self, mut f:F) where F:FnMut(&T)->bool {
+        self.retain_mut(|t| f(t));
+    }
+    fn retain_mut<F>(&mut self, mut f:F) where F:FnMut(&mut T)->bool {
+        let mut g = Backshift::new(self);
+        while g.processed<g.len {
+            let p = unsafe { g.data.add(g.processed) };
+            if f(unsafe { &mut *p }) {
+                if g.deleted>0 { unsafe { ptr::copy_nonoverlapping(p, p.sub(g.deleted), 1); } }
+                g.processed+=1;
+            } else {
+                g.processed+=1; g.deleted+=1;  // Count it before dropping, in case the drop panics.
+                unsafe { ptr::drop_in_place(p); }
             }
         }
-        // Maybe place this into the above impl when Type Equality Bounding is implemented):
-        // https://github.com/rust-lang/rust/issues/20041
-        impl $svec<u8> {
-            #[inline]
-            pub fn as_string(&self) -> Result<String, KErr> {
-                String::from_utf8(  self.iter().cloned().collect::<Vec<u8>>()  ).map_err(|_| KErr::new("Utf8Error"))
+    }
+    #[inline]
+    fn dedup(&mut self) where T:PartialEq {
+        self.dedup_by(|a,b| a==b);
+    }
+    // 'same_bucket' gets (item, previous kept item).  If it returns true, the item is removed.
+    fn dedup_by<F>(&mut self, mut same_bucket:F) where F:FnMut(&mut T, &mut T)->bool {
+        if self.length<=1 { return }
+        let mut g = Backshift::new(self);
+        g.processed = 1;  // The first item is always kept.
+        while g.processed<g.len {
+            let p = unsafe { g.data.add(g.processed) };
+            let kept = unsafe { g.data.add(g.processed-g.deleted-1) };
+            if same_bucket(unsafe { &mut *p }, unsafe { &mut *kept }) {
+                g.processed+=1; g.deleted+=1;
+                unsafe { ptr::drop_in_place(p); }
+            } else {
+                if g.deleted>0 { unsafe { ptr::copy_nonoverlapping(p, kept.add(1), 1); } }
+                g.processed+=1;
             }
         }
+    }
+    #[inline]
+    fn dedup_by_key<K,F>(&mut self, mut key:F) where F:FnMut(&mut T)->K, K:PartialEq {
+        self.dedup_by(|a,b| key(a)==key(b));
+    }
 
-        impl<T> Drop for $svec<T> {
-            fn drop(&mut self) {
-                //eprintln!("svec drop start");
-                self.clear();
-                //eprintln!("svec drop end");
-            }
+    // O(1), but the last item takes the place of the removed one.
+    fn swap_remove(&mut self, i:usize) -> T {
+        match self.try_swap_remove(i) {
+            Some(t) => t,
+            None => panic!("{}", StackedError::OutOfBounds{ index:i, len:self.length }),
         }
+    }
+    fn try_swap_remove(&mut self, i:usize) -> Option<T> {
+        if i>=self.length { return None }
+        unsafe {
+            self.length-=1;
+            let p = self.as_mut_ptr();
+            let t = ptr::read(p.add(i));
+            ptr::copy(p.add(self.length), p.add(i), 1);  // (When i is the last item, this is a harmless self-copy.)
+            Some(t)
+        }
+    }
 
-        impl<T> Index<usize> for $svec<T> {
-            type Output = T;
-            fn index(&self, index:usize) -> &Self::Output {
-                if index>=self.length { panic!("out-of-bounds"); }
-                self.data[index].as_ref().unwrap()
-            }
+    // Does nothing if 'len' is not smaller than the current length.
+    fn truncate(&mut self, len:usize) {
+        if len>=self.length { return }
+        let tail = ptr::slice_from_raw_parts_mut(unsafe { self.as_mut_ptr().add(len) }, self.length-len);
+        self.length = len;  // Before dropping, same as clear().
+        unsafe { ptr::drop_in_place(tail); }
+    }
+    // Grows by cloning 't', or shrinks like truncate().  If 'len' is past the capacity, nothing is changed.
+    fn resize(&mut self, len:usize, t:T) -> Result<(),StackedError> where T:Clone {
+        if len>N { return Err(StackedError::Overflow{ cap:N, rejected:len-N }); }
+        if len<=self.length { self.truncate(len); return Ok(()) }
+        while self.length<len-1 {
+            unsafe { ptr::write(self.as_mut_ptr().add(self.length), t.clone()); }
+            self.length+=1;
         }
-        impl<T> IndexMut<usize> for $svec<T> {
-            fn index_mut(&mut self, index:usize) -> &mut Self::Output {
-                if index>=self.length { panic!("out-of-bounds"); }
-                self.data[index].as_mut().unwrap()
-            }
+        unsafe { ptr::write(self.as_mut_ptr().add(self.length), t); }  // Move the original into the last slot.
+        self.length+=1;
+        Ok(())
+    }
+    fn resize_with<F>(&mut self, len:usize, mut f:F) -> Result<(),StackedError> where F:FnMut()->T {
+        if len>N { return Err(StackedError::Overflow{ cap:N, rejected:len-N }); }
+        if len<=self.length { self.truncate(len); return Ok(()) }
+        while self.length<len {
+            unsafe { ptr::write(self.as_mut_ptr().add(self.length), f()); }
+            self.length+=1;
         }
+        Ok(())
+    }
 
-        //////// We can't do this because into_iter() wants to take ownership of 'self', and that doesn't play nicely with stack allocations.
-        // impl<T> IntoIterator for $svec<T> {
-        //     type Item = T;
-        //     type IntoIter = $intoiter<T>;
-        //     fn into_iter(self) -> Self::IntoIter {
-        //         // mem::transmute::<Self, Self::IntoIter>(self)  // Can't transmute generics cuz compiler doesn't know their sizes (even though it totally could).
-        // 
-        //         // Here's my own transmute implementation:
-        //         assert_eq!(mem::size_of::<$svec<T>>(), mem::size_of::<$intoiter<T>>());
-        //         unsafe {
-        //             let iter = ptr::read(&self as *const $svec<T> as *const $intoiter<T>);
-        //             mem::forget(self);
-        //             iter
-        //         }
-        //     }
-        // }
-        impl<'a,T> IntoIterator for &'a $svec<T> {
-            type Item = &'a T;
-            type IntoIter = Box<dyn Iterator<Item=Self::Item> + 'a>;
-            fn into_iter(self) -> Self::IntoIter {
-                self.iter()
-            }
+    // Moves items at..len into a new SVec of the same capacity.
+    fn split_off(&mut self, at:usize) -> Self {
+        if at>self.length { panic!("{}", StackedError::OutOfBounds{ index:at, len:self.length }); }
+        let mut other = self.new_of::<T>();
+        unsafe { ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), self.length-at); }
+        other.length = self.length-at;
+        self.length = at;
+        other
+    }
+
+    #[inline]
+    fn get(&self, i:usize) -> Option<&T> {
+        if i>=self.length { return None }
+        Some(unsafe { &*self.as_ptr().add(i) })
+    }
+    #[inline]
+    fn get_mut(&mut self, i:usize) -> Option<&mut T> {
+        if i>=self.length { return None }
+        Some(unsafe { &mut *self.as_mut_ptr().add(i) })
+    }
+    #[inline]
+    fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+    #[inline]
+    fn last(&self) -> Option<&T> {
+        if self.length==0 { return None }
+        self.get(self.length-1)
+    }
+
+    fn reverse(&mut self) {
+        self.as_mut_slice().reverse();
+    }
+
+    #[inline]
+    fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.length) }
+    }
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.length) }
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        self.data.as_ptr() as *const T
+    }
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.data.as_mut_ptr() as *mut T
+    }
+
+    #[inline]
+    fn iter(&self) -> Iter<'_,T> {
+        Iter(self.as_slice().iter())
+    }
+    #[inline]
+    fn iter_mut(&mut self) -> IterMut<'_,T> {
+        IterMut(self.as_mut_slice().iter_mut())
+    }
+    // 'for x in svec' (see IntoIterator below) consumes the whole SVec.
+    // Here is a similar thing that takes '&mut' instead and returns owned objects by popping them (so they come out in reverse order).
+    // rev() takes them from the front instead, and nth()/nth_back() drop the skipped items in one go.
+    #[inline]
+    fn iter_owned(&mut self) -> IterOwned<'_,T> {
+        let end = self.length;
+        self.length = 0;  // Until the iterator is dropped, pretend to be empty.  (If it gets leaked, so do the items.)
+        IterOwned{ data:self.as_mut_ptr(),
+                   length:&mut self.length,
+                   start:0,
+                   end,
+                   _items:PhantomData }
+    }
+
+}
+impl<T, const N:usize> StackVec<T,N> {
+    #[inline]
+    pub fn cap_of_type() -> usize { N }
+
+    // An empty SVec with the same capacity as this one, but (possibly) a different item type.
+    #[inline]
+    pub fn new_of<U>(&self) -> StackVec<U,N> { StackVec::<U,N>::new() }
+
+    // I'm not able to implement the TryFrom trait because of a conflict with a blanket impl.
+    //     impl<T,I> TryFrom<I> for StackVec<T,N> where I:IntoIterator<Item=T>
+    // So that's why I'm putting this here:
+    pub fn try_from_iter<I>(iter:I) -> Result<Self,StackedError> where I:IntoIterator<Item=T> {
+        let mut out = Self::new();
+        for t in iter { out.push(t)?; }
+        Ok(out)
+    }
+}
+// Text access for byte SVecs.  These validate in place and borrow, so nothing gets copied.
+// (StackString is always valid UTF-8, so it just has an infallible as_str().)
+impl<const N:usize> StackVec<u8,N> {
+    #[inline]
+    pub fn as_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.as_slice())
+    }
+    #[inline]
+    pub fn as_str_mut(&mut self) -> Result<&mut str, Utf8Error> {
+        str::from_utf8_mut(self.as_mut_slice())
+    }
+    // Only allocates if there are invalid bytes to replace.
+    #[inline]
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.as_slice())
+    }
+    // Copies into a heap String.  Prefer as_str() unless you really need an owned String.
+    #[inline]
+    pub fn as_string(&self) -> Result<String, StackedError> {
+        Ok(self.as_str()?.to_string())
+    }
+}
+
+impl<T, const N:usize> Drop for StackVec<T,N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N:usize> Deref for StackVec<T,N> {
+    type Target = [T];
+    #[inline]
+    fn deref(&self) -> &[T] { self.as_slice() }
+}
+impl<T, const N:usize> DerefMut for StackVec<T,N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] { self.as_mut_slice() }
+}
+impl<T, const N:usize> AsRef<[T]> for StackVec<T,N> {
+    #[inline]
+    fn as_ref(&self) -> &[T] { self.as_slice() }
+}
+impl<T, const N:usize> AsMut<[T]> for StackVec<T,N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T] { self.as_mut_slice() }
+}
+impl<T, const N:usize> Borrow<[T]> for StackVec<T,N> {
+    #[inline]
+    fn borrow(&self) -> &[T] { self.as_slice() }
+}
+impl<T, const N:usize> BorrowMut<[T]> for StackVec<T,N> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [T] { self.as_mut_slice() }
+}
+
+// Indexing works just like it does for slices, so ranges are accepted too:  svec[1..3]
+impl<T, I, const N:usize> Index<I> for StackVec<T,N> where I:SliceIndex<[T]> {
+    type Output = I::Output;
+    #[inline]
+    fn index(&self, index:I) -> &Self::Output {
+        Index::index(self.as_slice(), index)
+    }
+}
+impl<T, I, const N:usize> IndexMut<I> for StackVec<T,N> where I:SliceIndex<[T]> {
+    #[inline]
+    fn index_mut(&mut self, index:I) -> &mut Self::Output {
+        IndexMut::index_mut(self.as_mut_slice(), index)
+    }
+}
+
+// Moves the storage into the iterator, so no data is shared with (or left behind in) the original SVec.
+impl<T, const N:usize> IntoIterator for StackVec<T,N> {
+    type Item = T;
+    type IntoIter = IntoIter<T,N>;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        let svec = ManuallyDrop::new(self);  // The items now belong to the iterator.
+        IntoIter{ data:unsafe { ptr::read(&svec.data) },
+                  start:0,
+                  end:svec.length }
+    }
+}
+impl<'a, T, const N:usize> IntoIterator for &'a StackVec<T,N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a,T>;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl<'a, T, const N:usize> IntoIterator for &'a mut StackVec<T,N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a,T>;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// The std collection traits have no way to report an overflow, so FromIterator and Extend panic when they run out of room.
+// That goes for every container in this crate (StackDeque, SMap, SSet, ...), and each one also has a fallible way in,
+// like try_from_iter() and try_extend() here.
+impl<T, const N:usize> iter::FromIterator<T> for StackVec<T,N> {
+    fn from_iter<I>(iter:I) -> Self where I:IntoIterator<Item=T> {
+        Self::try_from_iter(iter).unwrap()
+    }
+}
+impl<T, const N:usize> Extend<T> for StackVec<T,N> {
+    fn extend<I>(&mut self, iter:I) where I:IntoIterator<Item=T> {
+        if let Err(err) = self.try_extend(iter) { panic!("{}", err.error); }
+    }
+}
+impl<'a, T, const N:usize> Extend<&'a T> for StackVec<T,N> where T:Copy+'a {
+    fn extend<I>(&mut self, iter:I) where I:IntoIterator<Item=&'a T> {
+        if let Err(err) = self.try_extend(iter.into_iter().copied()) { panic!("{}", err.error); }
+    }
+}
+// This used to be a blanket 'impl From<I> where I:IntoIterator<Item=T>', but now that StackVec is
+// an IntoIterator itself, that conflicts with the std 'impl From<T> for T'.  So just cover the common sources:
+impl<T, const N:usize> From<Vec<T>> for StackVec<T,N> {
+    fn from(v:Vec<T>) -> Self {
+        Self::try_from_iter(v).unwrap()
+    }
+}
+impl<T, const N:usize, const M:usize> From<[T; M]> for StackVec<T,N> {
+    fn from(a:[T; M]) -> Self {
+        Self::try_from_iter(a).unwrap()
+    }
+}
+
+// Equality works across SVec sizes, element types (when T:PartialEq<U>), and against slices, arrays and Vecs (in both directions):
+impl<T, U, V, const N:usize> PartialEq<V> for StackVec<T,N> where T:PartialEq<U>, V:SVec<Item=U, Output=U> {
+    #[inline]
+    fn eq(&self, other:&V) -> bool { self.as_slice()==other.as_slice() }
+}
+macro_rules! impl_slice_eq {
+    ( [$($vars:tt)*] $lhs:ty, $rhs:ty ) => {
+        impl<$($vars)*> PartialEq<$rhs> for $lhs where T:PartialEq<U> {
+            #[inline]
+            fn eq(&self, other:&$rhs) -> bool { self[..]==other[..] }
         }
-        impl<'a,T> IntoIterator for &'a mut $svec<T> {
-            type Item = &'a mut T;
-            type IntoIter = Box<dyn Iterator<Item=Self::Item> + 'a>;
-            fn into_iter(self) -> Self::IntoIter {
-                self.iter_mut()
-            }
+    }
+}
+impl_slice_eq!([    T, U, const N:usize]                StackVec<T,N>, [U]);
+impl_slice_eq!([    T, U, const N:usize]                [T], StackVec<U,N>);
+impl_slice_eq!(['a, T, U, const N:usize]                StackVec<T,N>, &'a [U]);
+impl_slice_eq!(['a, T, U, const N:usize]                &'a [T], StackVec<U,N>);
+impl_slice_eq!([    T, U, const N:usize, const M:usize] StackVec<T,N>, [U; M]);
+impl_slice_eq!([    T, U, const N:usize, const M:usize] [T; M], StackVec<U,N>);
+impl_slice_eq!(['a, T, U, const N:usize, const M:usize] StackVec<T,N>, &'a [U; M]);
+impl_slice_eq!(['a, T, U, const N:usize, const M:usize] &'a [T; M], StackVec<U,N>);
+impl_slice_eq!([    T, U, const N:usize]                StackVec<T,N>, Vec<U>);
+impl_slice_eq!([    T, U, const N:usize]                Vec<T>, StackVec<U,N>);
+
+impl<T, const N:usize> Eq for StackVec<T,N> where T:Eq {}
+
+// Ordering and hashing match the slice impls, so lookups through Borrow<[T]> work (e.g. a HashMap<SVec8<u8>,_> can be queried with a &[u8]).
+impl<T, V, const N:usize> PartialOrd<V> for StackVec<T,N> where T:PartialOrd, V:SVec<Item=T, Output=T> {
+    #[inline]
+    fn partial_cmp(&self, other:&V) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+impl<T, const N:usize> Ord for StackVec<T,N> where T:Ord {
+    #[inline]
+    fn cmp(&self, other:&Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+impl<T, const N:usize> Hash for StackVec<T,N> where T:Hash {
+    #[inline]
+    fn hash<H>(&self, state:&mut H) where H:Hasher {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T, const N:usize> Default for StackVec<T,N> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+// Unfortunately, we can't also be Copy when T:Copy, because a type that implements Drop can never be Copy,
+// and we need Drop for the non-Copy case.
+impl<T, const N:usize> Clone for StackVec<T,N> where T:Clone {
+    fn clone(&self) -> Self {
+        let mut out = Self::new();
+        for t in self.iter() {
+            unsafe { ptr::write(out.as_mut_ptr().add(out.length), t.clone()); }
+            out.length+=1;  // Bump as we go, so a panicking clone() can't leak or double-drop.
         }
+        out
+    }
+    // Reuses the items we already have (and whatever resources they own), instead of dropping and re-cloning everything.
+    fn clone_from(&mut self, source:&Self) {
+        self.truncate(source.length);
+        let (prefix, rest) = source.split_at(self.length);
+        self.as_mut_slice().clone_from_slice(prefix);
+        if self.extend_from_slice(rest).is_err() { unreachable!() }  // Same capacity, so it always fits.
+    }
+}
 
-        impl<T> iter::FromIterator<T> for $svec<T> {
-            fn from_iter<I>(iter:I) -> Self where I:IntoIterator<Item=T> {
-                Self::try_from_iter(iter).unwrap()
-            }
+impl<T, const N:usize> fmt::Display for StackVec<T,N> where T:fmt::Display {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut nonempty = false;
+        write!(f, "[")?;
+        for t in self.iter() {
+            if nonempty { write!(f, ",")?; }
+            nonempty = true;
+            write!(f, " {}", t)?;
         }
-        // I'm surprised this isn't already handled by a blanket impl on top of FromIterator:
-        impl<T,I> From<I> for $svec<T> where I:IntoIterator<Item=T> {
-            fn from(iter:I) -> Self {
-                Self::try_from_iter(iter).unwrap()
-            }
+        if nonempty { write!(f, " ")?; }
+        write!(f, "]")?;
+        Ok(())
+    }
+}
+impl<T, const N:usize> fmt::Debug for StackVec<T,N> where T:fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut nonempty = false;
+        write!(f, "SVec{}[", N)?;
+        for t in self.iter() {
+            if nonempty { write!(f, ",")?; }
+            nonempty = true;
+            write!(f, " {:?}", t)?;
         }
+        if nonempty { write!(f, " ")?; }
+        write!(f, "]")?;
+        Ok(())
+    }
+}
 
-        impl<T,V> PartialEq<V> for $svec<T> where T:PartialEq, V:SVec<Item=T, Output=T> {
-            fn eq(&self, other:&V) -> bool {
-                if self.length!=other.len() { return false }
-                for i in 0..self.length {
-                    if self[i]!=other[i] { return false }
-                }
-                true
-            }
-        }
+// An owning iterator.  Only the slots in start..end are still initialized.
+pub struct IntoIter<T, const N:usize> {
+    data: [MaybeUninit<T>; N],
+    start: usize,
+    end: usize,
+}
+impl<T, const N:usize> IntoIter<T,N> {
+    // The items that have not been yielded yet.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts((self.data.as_ptr() as *const T).add(self.start), self.end-self.start) }
+    }
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut((self.data.as_mut_ptr() as *mut T).add(self.start), self.end-self.start) }
+    }
+}
+impl<T, const N:usize> Iterator for IntoIter<T,N> {
+    type Item = T;
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.start==self.end { return None }
+        self.start+=1;
+        Some(unsafe { self.data[self.start-1].assume_init_read() })
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.end-self.start;
+        (n, Some(n))
+    }
+}
+impl<T, const N:usize> DoubleEndedIterator for IntoIter<T,N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.start==self.end { return None }
+        self.end-=1;
+        Some(unsafe { self.data[self.end].assume_init_read() })
+    }
+}
+impl<T, const N:usize> ExactSizeIterator for IntoIter<T,N> {}
+impl<T, const N:usize> FusedIterator for IntoIter<T,N> {}
+impl<T, const N:usize> Drop for IntoIter<T,N> {
+    fn drop(&mut self) {
+        // Drop the items that were never consumed:
+        let remaining = self.as_mut_slice() as *mut [T];
+        self.start = self.end;
+        unsafe { ptr::drop_in_place(remaining); }
+    }
+}
 
-        // I can't figure out how to turn this into a blanket implementation...
-        // The difficulty is maybe because the Trait and the Type are both parameterized,
-        // and so i can't figure out how to specify those parameters along with the extra
-        // Display constraint...  I'm sure it's easy, but I can't figure it out.
-        impl<T> fmt::Display for $svec<T> where T:fmt::Display {
-            fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
-                let mut nonempty = false;
-                write!(f, "[")?;
-                for t in self.iter() {
-                    if nonempty { write!(f, ",")?; }
-                    nonempty = true;
-                    write!(f, " {}", t)?;
-                }
-                if nonempty { write!(f, " ")?; }
-                write!(f, "]")?;
-                Ok(())
-            }
+// Pops items off the end of the SVec as it goes (or off the front, with rev()).  Whatever is not consumed stays in the SVec:
+// while this exists, the SVec's length is 0, and dropping it moves the leftovers back down to the start.
+pub struct IterOwned<'a,T> {
+    data: *mut T,
+    length: &'a mut usize,
+    start: usize,       // start..end are the items that haven't been yielded.
+    end: usize,
+    _items: PhantomData<&'a mut [T]>,
+}
+impl<T> IterOwned<'_,T> {
+    // The items that have not been yielded yet.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data.add(self.start), self.end-self.start) }
+    }
+}
+impl<T> Iterator for IterOwned<'_,T> {
+    type Item = T;
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.start==self.end { return None }
+        self.end-=1;
+        Some(unsafe { ptr::read(self.data.add(self.end)) })
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.end-self.start;
+        (n, Some(n))
+    }
+    // Drops the skipped items all at once instead of popping them one by one.
+    #[inline]
+    fn nth(&mut self, n:usize) -> Option<T> {
+        let n = n.min(self.end-self.start);
+        let skipped = ptr::slice_from_raw_parts_mut(unsafe { self.data.add(self.end-n) }, n);
+        self.end-=n;  // (Before dropping, like truncate().)
+        unsafe { ptr::drop_in_place(skipped); }
+        self.next()
+    }
+}
+impl<T> DoubleEndedIterator for IterOwned<'_,T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.start==self.end { return None }
+        self.start+=1;
+        Some(unsafe { ptr::read(self.data.add(self.start-1)) })
+    }
+    #[inline]
+    fn nth_back(&mut self, n:usize) -> Option<T> {
+        let n = n.min(self.end-self.start);
+        let skipped = ptr::slice_from_raw_parts_mut(unsafe { self.data.add(self.start) }, n);
+        self.start+=n;
+        unsafe { ptr::drop_in_place(skipped); }
+        self.next_back()
+    }
+}
+impl<T> ExactSizeIterator for IterOwned<'_,T> {}
+// Same reasoning as for Drain.
+unsafe impl<T> Send for IterOwned<'_,T> where T:Send {}
+unsafe impl<T> Sync for IterOwned<'_,T> where T:Sync {}
+impl<T> FusedIterator for IterOwned<'_,T> {}
+impl<T> Drop for IterOwned<'_,T> {
+    fn drop(&mut self) {
+        let n = self.end-self.start;
+        unsafe { ptr::copy(self.data.add(self.start), self.data, n); }
+        *self.length = n;
+    }
+}
+
+// Used by retain/dedup.  Items in 0..processed have been visited, and 'deleted' of them were dropped; the survivors
+// have already been shifted down.  On drop (normal or panic), the unvisited tail is shifted down too, and the length is fixed up.
+struct Backshift<'a,T> {
+    data: *mut T,
+    length: &'a mut usize,
+    len: usize,
+    processed: usize,
+    deleted: usize,
+}
+impl<'a,T> Backshift<'a,T> {
+    #[inline]
+    fn new<const N:usize>(svec:&'a mut StackVec<T,N>) -> Self {
+        let len = svec.length;
+        svec.length = 0;  // Until we're done, pretend to be empty so nothing can get double-dropped.
+        Backshift{ data:svec.as_mut_ptr(),
+                   length:&mut svec.length,
+                   len,
+                   processed:0,
+                   deleted:0 }
+    }
+}
+impl<T> Drop for Backshift<'_,T> {
+    fn drop(&mut self) {
+        if self.deleted>0 {
+            unsafe { ptr::copy(self.data.add(self.processed), self.data.add(self.processed-self.deleted), self.len-self.processed); }
         }
-        impl<T> fmt::Debug for $svec<T> where T:fmt::Debug {
-            fn fmt(&self, f:&mut fmt::Formatter) -> Result<(), fmt::Error> {
-                let mut nonempty = false;
-                write!(f, "{}[", stringify!($svec))?;
-                for t in self.iter() {
-                    if nonempty { write!(f, ",")?; }
-                    nonempty = true;
-                    write!(f, " {:?}", t)?;
-                }
-                if nonempty { write!(f, " ")?; }
-                write!(f, "]")?;
-                Ok(())
+        *self.length = self.len-self.deleted;
+    }
+}
+
+// Yields the drained range front-to-back (or back-to-front).  While this exists, the SVec's length only covers the
+// items before the range.  Dropping it drops any items that weren't yielded, and then closes the gap by moving the tail.
+pub struct Drain<'a,T> {
+    data: *mut T,
+    length: &'a mut usize,
+    start: usize,       // Where the tail will be moved to.
+    next: usize,        // next..end are the items that still need to be yielded.
+    end: usize,
+    tail_start: usize,
+    tail_len: usize,
+    _items: PhantomData<&'a mut [T]>,
+}
+impl<T> Drain<'_,T> {
+    // The items that have not been yielded yet.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data.add(self.next), self.end-self.next) }
+    }
+}
+impl<T> Iterator for Drain<'_,T> {
+    type Item = T;
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.next==self.end { return None }
+        self.next+=1;
+        Some(unsafe { ptr::read(self.data.add(self.next-1)) })
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.end-self.next;
+        (n, Some(n))
+    }
+}
+impl<T> DoubleEndedIterator for Drain<'_,T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.next==self.end { return None }
+        self.end-=1;
+        Some(unsafe { ptr::read(self.data.add(self.end)) })
+    }
+}
+impl<T> ExactSizeIterator for Drain<'_,T> {}
+// The raw pointer opts us out of these, but we're really just a '&mut [T]', so follow its rules (like std's Drain does):
+unsafe impl<T> Send for Drain<'_,T> where T:Send {}
+unsafe impl<T> Sync for Drain<'_,T> where T:Sync {}
+impl<T> FusedIterator for Drain<'_,T> {}
+impl<T> Drop for Drain<'_,T> {
+    fn drop(&mut self) {
+        // Moves the tail even if one of the destructors below panics:
+        struct TailGuard<'r,'a,T>(&'r mut Drain<'a,T>);
+        impl<T> Drop for TailGuard<'_,'_,T> {
+            fn drop(&mut self) {
+                let d = &mut *self.0;
+                unsafe { ptr::copy(d.data.add(d.tail_start), d.data.add(d.start), d.tail_len); }
+                *d.length = d.start+d.tail_len;
             }
         }
 
+        let remaining = ptr::slice_from_raw_parts_mut(unsafe { self.data.add(self.next) }, self.end-self.next);
+        self.next = self.end;
+        let _guard = TailGuard(self);
+        unsafe { ptr::drop_in_place(remaining); }
+    }
+}
 
-        pub type $sstring = $svec<u8>;
-        // ---- I wanted to use this newtype, but it runs 50x slower than the above alias!!!
-        //      The compiler does a *horrible* job of handling this simple case...
-        //      I think it's because I was hitting a very perfect scenario where the compiler
-        //      produced code with almost no jumps and very localized code and data, which
-        //      resulted in some extremely fast benches.  Combined with the fact that the
-        //      compiler doesn't try to inline functions across crates, and the extra fn call
-        //      was a killer for the tight-loop.
-        // pub struct $sstring(pub $svec<u8>)
-        // impl $sstring {
-        //     pub fn new() -> Self { Self($svec::new()) }
-        // }
-        // impl SString for $sstring {
-        //     #[inline]
-        //     fn cap() -> usize { $svec::<u8>::cap() }
-        //     #[inline]
-        //     fn len(&self) -> usize { self.0.len() }
-        //     #[inline]
-        //     fn push(&self, b:u8) -> Result<usize,KErr> { self.0.push(b) }
-        // }
-    }
-}
-
-pub struct IntoIter<'a,T>(&'a mut dyn SVec<Item=T, Output=T>);
-impl<'a,T> Iterator for IntoIter<'a,T> {
-    type Item = T;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.0.len()==0 { return None }
-        Some(self.0.pop())
-    }
-}
-impl<T> IntoIter<'_,T> {
-    pub fn new<'a>(svec:&'a mut dyn SVec<Item=T, Output=T>) -> IntoIter<'a,T> {
-        IntoIter(svec)
-    }
-
-    //pub fn dataptr(&self) -> *const T { self.0.dataptr() }
-}
-
-//pub struct Iter<'a,T:'a> {
-//    svec: &'a dyn SVec<Item=T, Output=T>,
-//    next_i: usize,
-//}
-//impl<'a,T> Iterator for Iter<'a,T> {
-//    type Item = &'a T;
-//    fn next(&mut self) -> Option<Self::Item> {
-//        if self.next_i>=self.svec.len() { return None }
-//        self.next_i+=1;
-//        Some(&self.svec[self.next_i-1])
-//    }
-//}
-//impl<T> Iter<'_,T> {
-//    pub fn new<'a>(svec:&'a dyn SVec<Item=T, Output=T>) -> Iter<'a,T> {
-//        Iter{svec:svec, next_i:0}
-//    }
-//}
-
-//pub struct IterMut<'a,T:'a> {
-//    svec: &'a mut (dyn SVec<Item=T, Output=T> + 'a),
-//    next_i: usize,
-//}
-//impl<'a,T> Iterator for IterMut<'a,T> where T:'a {
-//    type Item = &'a mut T;
-//    fn next<'b>(&'b mut self) -> Option<&'a mut T> where 'a:'b {
-//        if self.next_i>=self.svec.len() { return None }
-//        self.next_i+=1;
-//        Some(&mut self.svec[self.next_i-1])
-//    }
-//}
-//impl<T> IterMut<'_,T> {
-//    pub fn new<'a>(svec:&'a mut dyn SVec<Item=T, Output=T>) -> IterMut<'a,T> {
-//        IterMut{svec:svec, next_i:0}
-//    }
-//}
+// Thin wrappers around the slice iterators, so that we are free to change the representation later.
+// Everything here is allocation-free, and len/nth/rev are all constant-time.
+#[derive(Clone)]
+pub struct Iter<'a,T>(pub(crate) slice::Iter<'a,T>);
+impl<'a,T> Iter<'a,T> {
+    #[inline]
+    pub fn as_slice(&self) -> &'a [T] { self.0.as_slice() }
+}
+impl<'a,T> Iterator for Iter<'a,T> {
+    type Item = &'a T;
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> { self.0.next() }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+    #[inline]
+    fn nth(&mut self, n:usize) -> Option<&'a T> { self.0.nth(n) }
+    #[inline]
+    fn count(self) -> usize { self.0.len() }
+    #[inline]
+    fn last(self) -> Option<&'a T> { self.0.last() }
+}
+impl<'a,T> DoubleEndedIterator for Iter<'a,T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> { self.0.next_back() }
+    #[inline]
+    fn nth_back(&mut self, n:usize) -> Option<&'a T> { self.0.nth_back(n) }
+}
+impl<T> ExactSizeIterator for Iter<'_,T> {
+    #[inline]
+    fn len(&self) -> usize { self.0.len() }
+}
+impl<T> FusedIterator for Iter<'_,T> {}
 
+pub struct IterMut<'a,T>(slice::IterMut<'a,T>);
+impl<'a,T> IterMut<'a,T> {
+    #[inline]
+    pub fn into_slice(self) -> &'a mut [T] { self.0.into_slice() }
+}
+impl<'a,T> Iterator for IterMut<'a,T> {
+    type Item = &'a mut T;
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> { self.0.next() }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+    #[inline]
+    fn nth(&mut self, n:usize) -> Option<&'a mut T> { self.0.nth(n) }
+    #[inline]
+    fn count(self) -> usize { self.0.len() }
+    #[inline]
+    fn last(self) -> Option<&'a mut T> { self.0.last() }
+}
+impl<'a,T> DoubleEndedIterator for IterMut<'a,T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> { self.0.next_back() }
+    #[inline]
+    fn nth_back(&mut self, n:usize) -> Option<&'a mut T> { self.0.nth_back(n) }
+}
+impl<T> ExactSizeIterator for IterMut<'_,T> {
+    #[inline]
+    fn len(&self) -> usize { self.0.len() }
+}
+impl<T> FusedIterator for IterMut<'_,T> {}
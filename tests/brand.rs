@@ -0,0 +1,46 @@
+use stacked::{SVec, StackedError, SVec4, SVec8};
+
+#[test]
+fn push_and_lookup() {
+    let mut v = SVec8::<&str>::new();
+    v.push("zero").unwrap();
+    let total = v.brand(|mut b| {
+        let zero = b.first().unwrap();
+        let one = b.push("one").unwrap();
+        let two = b.push("two").unwrap();
+        assert_eq!(one.index(), 1);
+        assert_eq!(b[zero], "zero");
+        assert_eq!(*b.get(two), "two");
+
+        b[one] = "ONE";
+        b.swap(zero, two);
+        assert_eq!(b.as_slice(), ["two", "ONE", "zero"]);
+        assert_eq!(b.last(), b.idx(2));
+        assert!(b.idx(3).is_none());
+        assert_eq!(format!("{:?}", two), "Idx(2)");
+
+        b.indices().map(|i| b[i].len()).sum::<usize>()
+    });
+    assert_eq!(total, 10);
+    assert_eq!(v, ["two", "ONE", "zero"]);
+}
+
+#[test]
+fn indices_survive_appends() {
+    let mut v = SVec4::<u32>::new();
+    v.brand(|mut b| {
+        assert!(b.is_empty());
+        let first = b.push(1).unwrap();
+        let idxs : Vec<_> = (2..=4).map(|n| b.push(n).unwrap()).collect();
+        assert_eq!(b.push(5).unwrap_err(), (5, StackedError::Overflow{ cap:4, rejected:1 }));
+        for i in idxs { b[i] *= 10; }
+        *b.get_mut(first) += 100;
+        assert_eq!(b.len(), b.cap());
+        assert_eq!(b.indices().next_back().map(|i| b[i]), Some(40));
+    });
+    assert_eq!(v, [101, 20, 30, 40]);
+
+    // A second brand() call starts from scratch.  (The compiler won't let the old indexes in here.)
+    v.truncate(1);
+    v.brand(|b| assert_eq!(b.indices().len(), 1));
+}
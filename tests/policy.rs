@@ -0,0 +1,63 @@
+use stacked::{SVec, StackedError, StackVec, Bounded, Reject, Panic, OverwriteOldest, DropNewest, SVec2, SVec4, SDeque4};
+
+#[test]
+fn reject() {
+    let mut v = Bounded::<SVec2<u8>, Reject>::new();
+    assert_eq!(v.push(1), Ok(()));
+    assert_eq!(v.push(2), Ok(()));
+    assert_eq!(v.push(3), Err((3, StackedError::Overflow{ cap:2, rejected:1 })));
+    assert_eq!(*v, [1,2]);
+}
+
+#[test]
+#[should_panic(expected="overflow: capacity is 2, 1 item(s) rejected")]
+fn reject_extend() {
+    // extend() can't hand the item back, so it panics like every other Extend in this crate.
+    let mut v = Bounded::<SVec2<u8>, Reject>::new();
+    v.extend(vec![1,2]);
+    v.extend(vec![3]);
+}
+
+#[test]
+#[should_panic(expected="overflow: capacity is 2, 1 item(s) rejected")]
+fn panic() {
+    let mut v = Bounded::<SVec2<u8>, Panic>::new();
+    v.push(1);
+    v.push(2);
+    v.extend(vec![3]);
+}
+
+#[test]
+fn overwrite_oldest() {
+    // Keep the 4 most recent samples:
+    let mut q = Bounded::<SDeque4<u32>, OverwriteOldest>::new();
+    let evicted : Vec<u32> = (0..10).filter_map(|i| q.push(i)).collect();
+    assert_eq!(evicted, vec![0,1,2,3,4,5]);
+    assert_eq!(q.iter().copied().collect::<Vec<_>>(), vec![6,7,8,9]);
+    // Bulk appends follow the policy too:
+    q.extend(10..13);
+    assert_eq!(q.iter().copied().collect::<Vec<_>>(), vec![9,10,11,12]);
+    let last : Bounded<SDeque4<u32>, OverwriteOldest> = (0..100).collect();
+    assert_eq!(last.iter().copied().collect::<Vec<_>>(), vec![96,97,98,99]);
+
+    // Also works on an SVec (but it's O(N) per eviction):
+    let mut v = Bounded::<SVec4<u32>, OverwriteOldest>::from(SVec4::from([1,2,3]));
+    assert_eq!(v.push(4), None);
+    assert_eq!(v.push(5), Some(1));
+    assert_eq!(v.get_mut().pop(), 5);  // Everything else goes through get_mut().
+    assert_eq!(v.into_inner(), [2,3,4]);
+
+    let mut zero = Bounded::<StackVec<u32,0>, OverwriteOldest>::new();
+    assert_eq!(zero.push(7), Some(7));
+}
+
+#[test]
+fn drop_newest() {
+    let mut q = Bounded::<SDeque4<&str>, DropNewest>::new();
+    for s in ["a","b","c","d"] { assert_eq!(q.push(s), None); }
+    assert_eq!(q.push("e"), Some("e"));
+    assert_eq!(q.get_mut().pop_front(), "a");
+    assert_eq!(q.push("f"), None);
+    q.extend(vec!["g"]);
+    assert_eq!(format!("{:?}", q), r#"SDeque4[ "b", "c", "d", "f" ]"#);
+}
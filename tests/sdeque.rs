@@ -0,0 +1,108 @@
+use stacked::{StackedError, SDeque2, SDeque4, SDeque8};
+
+use std::collections::HashSet;
+use std::rc::Rc;
+
+#[test]
+fn fifo() {
+    let mut q = SDeque4::<i32>::new();
+    assert_eq!(q.cap(), 4);
+    assert!(q.is_empty());
+    // Cycle through more items than the capacity, so the items wrap around the end of the storage:
+    for i in 0..4 { q.push_back(i).unwrap(); }
+    assert!(q.is_full());
+    assert_eq!(q.push_back(4), Err((4, StackedError::Overflow{ cap:4, rejected:1 })));
+    for i in 4..20 {
+        assert_eq!(q.pop_front(), i-4);
+        q.push_back(i).unwrap();
+        assert_eq!(q.len(), 4);
+        assert_eq!((q[0], q[3]), (i-3, i));
+    }
+    assert_eq!(q.iter().copied().collect::<Vec<_>>(), vec![16,17,18,19]);
+    assert_eq!(q.try_pop_front(), Some(16));
+    assert_eq!(q.try_pop_back(), Some(19));
+    assert_eq!(format!("{:?}", q), "SDeque4[ 17, 18 ]");
+
+    // After all that cycling, the items start partway into the storage.  Equality and hashing only care about the order:
+    let fresh : SDeque8<i32> = (17..19).collect();
+    assert_eq!(q, fresh);
+    let set : HashSet<SDeque4<i32>> = std::iter::once(q).collect();
+    assert!(set.contains(&(17..19).collect()));
+}
+
+#[test]
+fn both_ends() {
+    let mut q = SDeque8::<char>::new();
+    q.push_back('c').unwrap();
+    q.push_front('b').unwrap();
+    q.push_front('a').unwrap();
+    q.push_back('d').unwrap();
+    assert_eq!((q.front(), q.back()), (Some(&'a'), Some(&'d')));
+    *q.front_mut().unwrap() = 'A';
+    *q.back_mut().unwrap() = 'D';
+    q[1] = 'B';
+    assert_eq!(q.get(2), Some(&'c'));
+    assert_eq!(q.get(4), None);
+    assert_eq!(q.iter().rev().collect::<String>(), "DcBA");
+    for c in &mut q { c.make_ascii_lowercase(); }
+    assert_eq!(q.iter().len(), 4);
+    assert_eq!(q.clone().into_iter().collect::<String>(), "abcd");
+    assert_eq!(q.pop_back(), 'd');
+    assert_eq!(q.pop_front(), 'a');
+    q.extend("ef".chars());
+    assert_eq!(q.into_iter().rev().collect::<String>(), "fecb");
+}
+
+#[test]
+#[should_panic(expected="underflow")]
+fn pop_underflow() {
+    SDeque2::<i32>::new().pop_front();
+}
+
+#[test]
+#[should_panic(expected="out-of-bounds: index is 2 but len is 2")]
+fn index_out_of_bounds() {
+    let q : SDeque4<i32> = (0..2).collect();
+    let _ = q[2];
+}
+
+#[test]
+fn slices() {
+    let mut q = SDeque4::<i32>::new();
+    q.push_back(2).unwrap();
+    q.push_back(3).unwrap();
+    assert_eq!(q.as_slices(), (&[2,3][..], &[][..]));
+    q.push_front(1).unwrap();  // Wraps to the end of the storage.
+    q.push_front(0).unwrap();
+    assert_eq!(q.as_slices(), (&[0,1][..], &[2,3][..]));
+    {
+        let (a,b) = q.as_mut_slices();
+        a[0] = 10;
+        b[1] = 13;
+    }
+
+    let all = q.make_contiguous();
+    assert_eq!(all, [10,1,2,13]);
+    all.sort_by(|a,b| b.cmp(a));
+    assert_eq!(q.as_slices(), (&[13,10,2,1][..], &[][..]));
+    assert_eq!(q.make_contiguous(), [13,10,2,1]);  // Already contiguous.
+}
+
+#[test]
+fn truncate_across_wrap() {
+    // The interesting drops are when the tail is split in two:  part at the end of the storage, part at the start.
+    let token = Rc::new(());
+    let mut q = SDeque4::<Rc<()>>::new();
+    for _ in 0..2 { q.push_back(token.clone()).unwrap(); }
+    for _ in 0..2 { q.push_front(token.clone()).unwrap(); }
+    assert_eq!(q.as_slices().0.len(), 2);
+    q.truncate(1);  // Drops one item from the first piece, and both from the second.
+    assert_eq!((q.len(), Rc::strong_count(&token)), (1, 2));
+
+    q.push_front(token.clone()).unwrap();
+    q.push_front(token.clone()).unwrap();
+    let mut it = q.into_iter();  // Leftovers in an owning iterator get dropped too.
+    it.next_back();
+    drop(it);
+    assert_eq!(Rc::strong_count(&token), 1);
+}
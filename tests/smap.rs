@@ -0,0 +1,110 @@
+use stacked::{StackedError, SMap, Entry};
+
+use std::ops::Bound;
+
+#[test]
+fn basics() {
+    let mut m = SMap::<&str, i32, 4>::new();
+    assert!(m.is_empty());
+    assert_eq!(m.insert("b", 2), Ok(None));
+    assert_eq!(m.insert("d", 4), Ok(None));
+    assert_eq!(m.insert("a", 1), Ok(None));
+    assert_eq!(m.insert("b", 20), Ok(Some(2)));  // Replacing doesn't need room.
+    assert_eq!(m.insert("c", 3), Ok(None));
+    assert_eq!(m.insert("e", 5), Err((("e",5), StackedError::Overflow{ cap:4, rejected:1 })));
+    assert_eq!(m.insert("a", 10), Ok(Some(1)));  // Still fine when full.
+
+    assert_eq!(m.len(), 4);
+    assert_eq!(m.get("b"), Some(&20));
+    assert_eq!(m.get("z"), None);
+    assert_eq!(m["c"], 3);
+    assert!(m.contains_key("d"));
+    *m.get_mut("d").unwrap() += 1;
+    assert_eq!(m.get_key_value("d"), Some((&"d", &5)));
+
+    // Always in key order:
+    assert_eq!(m.keys().copied().collect::<Vec<_>>(), vec!["a","b","c","d"]);
+    assert_eq!(m.values().copied().collect::<Vec<_>>(), vec![10,20,3,5]);
+    assert_eq!(m.first_key_value(), Some((&"a", &10)));
+    assert_eq!(m.last_key_value(), Some((&"d", &5)));
+    assert_eq!(format!("{:?}", m), r#"SMap4{ "a": 10, "b": 20, "c": 3, "d": 5 }"#);
+
+    assert_eq!(m.remove("b"), Some(20));
+    assert_eq!(m.remove("b"), None);
+    assert_eq!(m.pop_first(), Some(("a",10)));
+    assert_eq!(m.pop_last(), Some(("d",5)));
+    assert_eq!(m.as_slice(), [("c",3)]);
+}
+
+#[test]
+fn borrowed_keys() {
+    let mut m = SMap::<String, u8, 8>::new();
+    m.insert("x".to_string(), 1).unwrap();
+    assert_eq!(m.get("x"), Some(&1));  // Look up a String key with a &str.
+    assert_eq!(m.remove_entry("x"), Some(("x".to_string(), 1)));
+}
+
+#[test]
+#[should_panic(expected="key not found")]
+fn index_missing() {
+    let m = SMap::<u8, u8, 2>::new();
+    let _ = m[&1];
+}
+
+#[test]
+fn entry() {
+    let mut counts = SMap::<char, usize, 8>::new();
+    for c in "abracadabra".chars() { *counts.entry(c).or_insert(0).unwrap() += 1; }
+    assert_eq!(counts.iter().map(|(c,n)| format!("{}{}", c, n)).collect::<String>(), "a5b2c1d1r2");
+
+    counts.entry('a').and_modify(|n| *n*=10).or_default().unwrap();
+    counts.entry('z').and_modify(|n| *n*=10).or_default().unwrap();
+    assert_eq!((counts[&'a'], counts[&'z']), (50, 0));
+
+    match counts.entry('r') {
+        Entry::Occupied(mut e) => {
+            assert_eq!(e.key(), &'r');
+            assert_eq!(e.insert(7), 2);
+            assert_eq!(e.remove(), 7);
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+    match counts.entry('q') {
+        Entry::Vacant(e) => { assert_eq!(e.key(), &'q'); *e.insert(1).unwrap() += 1; }
+        Entry::Occupied(_) => unreachable!(),
+    }
+    assert_eq!(counts.get(&'q'), Some(&2));
+    assert_eq!(counts.len(), 6);
+
+    // A vacant entry can overflow:
+    let mut full : SMap<u8,u8,1> = [(1,1)].iter().copied().collect();
+    assert_eq!(full.entry(1).or_insert(9), Ok(&mut 1));
+    assert_eq!(full.entry(2).or_insert(9), Err(((2,9), StackedError::Overflow{ cap:1, rejected:1 })));
+}
+
+#[test]
+fn ranges() {
+    let m : SMap<i32, char, 16> = (0..10).map(|i| (i*10, (b'a'+i as u8) as char)).collect();
+    let keys = |r:stacked::MapIter<i32,char>| r.map(|(k,_)| *k).collect::<Vec<_>>();
+    assert_eq!(keys(m.range(20..50)), vec![20,30,40]);
+    assert_eq!(keys(m.range(15..=50)), vec![20,30,40,50]);
+    assert_eq!(keys(m.range(..15)), vec![0,10]);
+    assert_eq!(keys(m.range(85..)), vec![90]);
+    assert_eq!(keys(m.range((Bound::Included(50), Bound::Excluded(20)))), vec![]);  // Backwards is just empty.
+    assert_eq!(m.range(..).next_back(), Some((&90, &'j')));
+    assert_eq!(m.range(30..40).len(), 1);
+}
+
+#[test]
+fn same_key_collect() {
+    // Duplicates collapse (the last one wins), so this fits in 2 slots:
+    let mut a : SMap<u8, String, 2> = vec![(2,"x".to_string()), (1,"one".to_string()), (2,"two".to_string())].into_iter().collect();
+    let b : SMap<u8, String, 8> = vec![(1,"one".to_string()), (2,"two".to_string())].into_iter().collect();
+    assert_eq!(a, b);  // Capacities don't have to match.
+    assert!(a < [(1,"one".to_string()), (3,"".to_string())].iter().cloned().collect::<SMap<u8,String,2>>());  // Compared pair by pair, in key order.
+
+    for (_,v) in &mut a { v.make_ascii_uppercase(); }
+    for v in a.values_mut() { v.push('!'); }
+    a.retain(|k,_| *k!=1);
+    assert_eq!(a.into_iter().collect::<Vec<_>>(), vec![(2,"TWO!".to_string())]);
+}
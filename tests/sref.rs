@@ -0,0 +1,68 @@
+use stacked::{SVec, StackedError, Tracked, SVec2, SVec8};
+
+use std::collections::HashSet;
+
+#[test]
+fn append_keeps_refs() {
+    let mut v = Tracked::<SVec8<&str>>::new();
+    let a = v.push_ref("a").unwrap();
+    assert_eq!(v.push("b"), Ok(1));
+    let c = v.push_ref("c").unwrap();
+    assert_eq!(c.index(), 2);
+    assert_eq!(v.generation(), 0);
+
+    assert_eq!(v.get(a), Some(&"a"));
+    assert_eq!(v[c], "c");
+    *v.get_mut(a).unwrap() = "A";
+    v[c] = "C";
+    assert_eq!(v.generation(), 0);  // In-place changes don't invalidate anything.
+    assert_eq!(*v, ["A", "b", "C"]);  // Reads go through Deref.
+    assert_eq!(v.len(), 3);
+
+    let b = v.sref(1).unwrap();
+    assert_eq!(v[b], "b");
+    assert!(v.sref(3).is_none());
+
+    let set : HashSet<_> = [a, b, c, a].iter().copied().collect();
+    assert_eq!(set.len(), 3);
+    assert_eq!(format!("{:?}", c), "SRef(2@0)");
+}
+
+#[test]
+fn mutation_invalidates() {
+    let mut v = Tracked::from(SVec8::<i32>::new());
+    let a = v.push_ref(10).unwrap();
+    let b = v.push_ref(20).unwrap();
+
+    v.mutate().remove(0);
+    assert_eq!(v.generation(), 1);
+    assert!(!v.is_valid(a));
+    assert_eq!(v.get(b), None);  // Raw index 1 is out of range now, but even in range it would be refused:
+    v.push(30).unwrap();
+    assert_eq!(v.get(b), None);
+    assert!(v.get_mut(a).is_none());
+
+    let b2 = v.sref(0).unwrap();
+    assert_eq!(v[b2], 20);
+    v.mutate().reverse();
+    assert!(v.get(b2).is_none());
+    assert_eq!(v.into_inner(), [30, 20]);
+}
+
+#[test]
+#[should_panic(expected = "stale SRef(0@0)")]
+fn stale_index_panics() {
+    let mut v = Tracked::<SVec2<u8>>::default();
+    let a = v.push_ref(1).unwrap();
+    v.mutate().clear();
+    v.push(2).unwrap();
+    let _ = v[a];
+}
+
+#[test]
+fn overflow() {
+    let mut v = Tracked::<SVec2<u8>>::new();
+    v.push_ref(1).unwrap();
+    v.push_ref(2).unwrap();
+    assert_eq!(v.push_ref(3).unwrap_err(), (3, StackedError::Overflow{ cap:2, rejected:1 }));
+}
@@ -0,0 +1,80 @@
+use stacked::{StackedError, SSet};
+
+#[test]
+fn basics() {
+    let mut s = SSet::<&str, 4>::new();
+    assert!(s.is_empty());
+    assert_eq!(s.insert("write"), Ok(true));
+    assert_eq!(s.insert("read"), Ok(true));
+    assert_eq!(s.insert("read"), Ok(false));
+    assert_eq!(s.insert("admin"), Ok(true));
+    assert_eq!(s.insert("exec"), Ok(true));
+    assert_eq!(s.insert("root"), Err(("root", StackedError::Overflow{ cap:4, rejected:1 })));
+    assert_eq!(s.insert("exec"), Ok(false));  // Already there, so it doesn't need room.
+
+    assert_eq!(s.as_slice(), ["admin","exec","read","write"]);
+    assert!(s.contains("read"));
+    assert!(!s.contains("root"));
+    assert_eq!(s.get("exec"), Some(&"exec"));
+    assert_eq!((s.first(), s.last()), (Some(&"admin"), Some(&"write")));
+    assert_eq!(format!("{:?}", s), r#"SSet4{ "admin", "exec", "read", "write" }"#);
+
+    assert!(s.remove("read"));
+    assert!(!s.remove("read"));
+    assert_eq!(s.take("exec"), Some("exec"));
+    assert_eq!(s.pop_first(), Some("admin"));
+    assert_eq!(s.pop_last(), Some("write"));
+    assert!(s.is_empty());
+
+    s.extend(vec!["d","a","c","b"]);
+    s.retain(|&x| x!="b");
+    assert_eq!((&s).into_iter().copied().collect::<String>(), "acd");
+    assert_eq!(s.into_iter().rev().collect::<String>(), "dca");
+}
+
+#[test]
+fn try_from_iter() {
+    let s = SSet::<u8, 3>::try_from_iter(vec![3,1,3,2,1]).unwrap();  // Duplicates don't count against the capacity.
+    assert_eq!(s.as_slice(), [1,2,3]);
+    assert_eq!(SSet::<u8, 3>::try_from_iter(0..10).unwrap_err(), StackedError::Overflow{ cap:3, rejected:1 });
+
+    let evens : SSet<u32, 16> = (0..20).step_by(2).collect();
+    assert_eq!(evens.range(5..=10).copied().collect::<Vec<_>>(), vec![6,8,10]);
+    assert_eq!(evens.range(15..).len(), 2);
+
+    let a : SSet<char, 2> = "abba".chars().collect();  // Fits, because collect() dedups too.
+    assert_eq!(a, "ba".chars().collect::<SSet<char,8>>());  // Capacities don't have to match.
+    assert!(a < "ac".chars().collect::<SSet<char,2>>());  // Compared item by item, in order.
+}
+
+#[test]
+fn algebra() {
+    let a : SSet<i32, 8> = [1,2,3,5,8].iter().copied().collect();
+    let b : SSet<i32, 4> = [2,3,4,9].iter().copied().collect();  // Capacities don't have to match.
+    let v = |it:&mut dyn Iterator<Item=&i32>| it.copied().collect::<Vec<_>>();
+
+    assert_eq!(v(&mut a.union(&b)), vec![1,2,3,4,5,8,9]);
+    assert_eq!(v(&mut a.intersection(&b)), vec![2,3]);
+    assert_eq!(v(&mut a.difference(&b)), vec![1,5,8]);
+    assert_eq!(v(&mut b.difference(&a)), vec![4,9]);
+    assert_eq!(v(&mut a.symmetric_difference(&b)), vec![1,4,5,8,9]);
+
+    // Build new sets from the results:
+    let u : SSet<i32, 8> = a.union(&b).collect_set().unwrap();
+    assert_eq!(u.len(), 7);
+    let i : SSet<i32, 2> = a.intersection(&b).collect_set().unwrap();
+    assert_eq!(i.as_slice(), [2,3]);
+    assert_eq!(a.union(&b).collect_set::<4>().unwrap_err(), StackedError::Overflow{ cap:4, rejected:3 });
+
+    assert!(i.is_subset(&a) && i.is_subset(&b));
+    assert!(a.is_superset(&i));
+    assert!(!a.is_subset(&b));
+    assert!(a.difference(&b).collect_set::<8>().unwrap().is_disjoint(&b));
+    assert!(!a.is_disjoint(&b));
+
+    let empty = SSet::<i32, 1>::new();
+    assert_eq!(a.union(&empty).count(), 5);
+    assert_eq!(empty.intersection(&a).count(), 0);
+    assert_eq!(a.union(&b).size_hint(), (5, Some(9)));
+}
+
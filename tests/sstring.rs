@@ -0,0 +1,263 @@
+use stacked::{StackedError, StackString, SString2, SString4, SString8, SString16};
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[test]
+fn basics() {
+    let mut s = SString16::new();
+    assert_eq!(s.cap(), 16);
+    assert!(s.is_empty());
+    s.push('H').unwrap();
+    s.push_str("ello").unwrap();
+    s.push('!').unwrap();
+    assert_eq!(s, "Hello!");
+    assert_eq!(s.len(), 6);
+    assert_eq!(s.to_string(), "Hello!");
+    assert_eq!(format!("{:?}", s), r#"SString16("Hello!")"#);
+
+    // Deref to str gives us the whole str toolbox:
+    assert!(s.starts_with("Hell"));
+    assert_eq!(s.find('o'), Some(4));
+    assert_eq!(&s[1..3], "el");
+    s.make_ascii_uppercase();
+    assert_eq!(s, "HELLO!");
+
+    s.insert_str(5, ", WORLD").unwrap();
+    assert_eq!(s, "HELLO, WORLD!");
+    s.insert(0, '¡').unwrap();
+    assert_eq!(s, "¡HELLO, WORLD!");
+
+    assert_eq!(s.pop(), Some('!'));
+    s.truncate(2);
+    assert_eq!(s, "¡");
+    assert_eq!(s.pop(), Some('¡'));
+    assert_eq!(s.pop(), None);
+}
+
+#[test]
+fn overflow() {
+    let mut s = SString4::new();
+    s.push_str("abc").unwrap();
+    assert_eq!(s.push('é'), Err(StackedError::Overflow{ cap:4, rejected:2 }));  // A 2-byte char doesn't fit in 1 byte.
+    assert_eq!(s.push_str("de"), Err(StackedError::Overflow{ cap:4, rejected:2 }));
+    assert_eq!(s, "abc");  // Unchanged.
+    s.push('d').unwrap();
+    assert_eq!(s.push('e'), Err(StackedError::Overflow{ cap:4, rejected:1 }));
+
+    assert_eq!("too long".parse::<SString4>(), Err(StackedError::Overflow{ cap:4, rejected:8 }));
+    assert_eq!(SString8::try_from("fits").unwrap(), "fits");
+}
+
+#[test]
+fn utf8_guarantees() {
+    let mut s : SString8 = "añb".parse().unwrap();
+    assert_eq!(s.insert_str(2, "x"), Err(StackedError::NotCharBoundary{ index:2 }));
+    assert_eq!(s.insert_str(9, "x"), Err(StackedError::OutOfBounds{ index:9, len:4 }));
+    assert_eq!(s, "añb");
+
+    let bytes : stacked::SVec8<u8> = vec![b'o', b'k', 0xff].into();
+    let (bytes, err) = SString8::from_utf8(bytes).unwrap_err();
+    assert_eq!(err, StackedError::InvalidUtf8{ valid_up_to:2 });
+    assert_eq!(bytes.len(), 3);
+
+    let good : stacked::SVec8<u8> = (*b"ok").into();
+    assert_eq!(SString8::from_utf8(good).unwrap().into_bytes(), b"ok");
+}
+
+#[test]
+#[should_panic(expected="not a char boundary")]
+fn truncate_mid_char() {
+    let mut s : SString8 = "ñ".parse().unwrap();
+    s.truncate(1);
+}
+
+#[test]
+fn std_traits() {
+    let a : SString8 = "key".parse().unwrap();
+    let b : StackString<100> = "key".parse().unwrap();
+    assert_eq!(a, b);
+    assert!(a < "kez".parse::<SString4>().unwrap());
+    assert_eq!(a.clone(), a);
+    assert_eq!(SString8::default(), "");
+
+    let mut hm = HashMap::new();
+    hm.insert(a, 1);
+    assert_eq!(hm.get("key"), Some(&1));
+}
+
+#[test]
+fn formatting() {
+    use std::fmt::Write;
+    use stacked::{sformat, SString64};
+
+    let mut s = SString16::new();
+    let (name, n, long) = ("id", 7, "way too long for this");
+    write!(s, "{}:{:03}", name, n).unwrap();
+    assert_eq!(s, "id:007");
+    assert!(write!(s, "{}", long).is_err());
+
+    let key = sformat!(SString64; "{}-{}", "user", 42).unwrap();
+    assert_eq!(key, "user-42");
+
+    assert_eq!(sformat!(SString8; "{}", "123456789"), Err(StackedError::Overflow{ cap:8, rejected:9 }));
+
+    let cut = sformat!(SString8, truncate; "{}", "123456789").unwrap();
+    assert_eq!(cut, "12345…");
+    let cut = sformat!(SString8, truncate="~"; "{}{}", "abc", "défghij").unwrap();
+    assert_eq!(cut, "abcdéf~");  // 'é' is 2 bytes.
+    let cut = sformat!(SString4, truncate="[...]"; "{}", "abcdef").unwrap();
+    assert_eq!(cut, "abcd");     // The marker doesn't fit, so just truncate.
+    let fits = sformat!(SString8, truncate; "{}", "fits").unwrap();
+    assert_eq!(fits, "fits");
+
+    struct Broken;
+    impl std::fmt::Display for Broken {
+        fn fmt(&self, _:&mut std::fmt::Formatter) -> std::fmt::Result { Err(std::fmt::Error) }
+    }
+    assert_eq!(sformat!(SString8; "{}", Broken), Err(StackedError::Fmt));
+    assert_eq!(sformat!(SString8, truncate; "{}", Broken), Err(StackedError::Fmt));
+
+    // A Display impl that ignores a failed write must not sneak later pieces in after the gap:
+    struct Sloppy;
+    impl std::fmt::Display for Sloppy {
+        fn fmt(&self, f:&mut std::fmt::Formatter) -> std::fmt::Result {
+            let _ = f.write_str("0123456789");
+            f.write_str("ab")
+        }
+    }
+    assert_eq!(sformat!(SString8; "{}", Sloppy), Err(StackedError::Overflow{ cap:8, rejected:10 }));
+    assert_eq!(sformat!(SString8, truncate; "{}", Sloppy).unwrap(), "01234…");
+    assert_eq!(sformat!(SString16; "{}", Sloppy).unwrap(), "0123456789ab");
+
+    // Straight onto the string, it leaves a gap (and even reports success).  writer() doesn't:
+    let mut s = SString8::new();
+    assert!(write!(s, "{}", Sloppy).is_ok());
+    assert_eq!(s, "ab");
+    let mut s = SString8::try_from("<").unwrap();
+    let mut w = s.writer();
+    assert!(write!(w, "{}", Sloppy).is_err());
+    assert_eq!(w.overflow(), Some(StackedError::Overflow{ cap:8, rejected:10 }));
+    assert!(write!(w, "!").is_err());  // Still refuses.
+    assert_eq!(s, "<");
+}
+
+#[test]
+fn numbers() {
+    use stacked::{SString64, SString256};
+
+    macro_rules! check_ints {
+        ( $($t:ty),* ) => { $(
+            for &n in &[<$t>::MIN, <$t>::MAX, 0 as $t, 1 as $t, 9 as $t, 10 as $t, 99 as $t, 100 as $t, <$t>::MAX/3] {
+                let mut s = SString64::new();
+                s.push_int(n).unwrap();
+                assert_eq!(s, n.to_string());
+                s.clear();
+                s.push_hex(n).unwrap();
+                assert_eq!(s, format!("{:x}", n));
+            }
+        )* }
+    }
+    check_ints!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+    let mut s = SString256::new();
+    for &x in &[0.0f64, -0.0, 1.0, 0.1, 1.5e-7, 123456.789, f64::MAX, f64::MIN_POSITIVE, 1e20] {
+        s.clear();
+        s.push_float(x).unwrap();
+        assert_eq!(s.parse::<f64>().unwrap().to_bits(), x.to_bits());  // Round-trips exactly.
+    }
+    s.clear();
+    s.push_float(0.3f32).unwrap();
+    assert_eq!(s, "0.3");
+    s.clear();
+    s.push_float(f64::NAN).unwrap();
+    s.push(' ').unwrap();
+    s.push_float(f64::NEG_INFINITY).unwrap();
+    assert_eq!(s, "NaN -inf");
+
+    // No partial output on overflow:
+    let mut s = SString4::new();
+    s.push_str("x=").unwrap();
+    assert_eq!(s.push_int(-100), Err(StackedError::Overflow{ cap:4, rejected:4 }));
+    assert_eq!(s.push_hex(0xabcu32), Err(StackedError::Overflow{ cap:4, rejected:3 }));
+    assert_eq!(s.push_float(1.25), Err(StackedError::Overflow{ cap:4, rejected:4 }));
+    assert_eq!(s, "x=");
+    s.push_int(42u8).unwrap();
+    assert_eq!(s, "x=42");
+}
+
+#[test]
+fn text_ops() {
+    use stacked::{SVec4, SVec8, SString32};
+
+    let mut s : SString32 = "  \t hello world \n".parse().unwrap();
+    s.trim_end();
+    assert_eq!(s, "  \t hello world");
+    s.trim_start();
+    assert_eq!(s, "hello world");
+    let mut t : SString32 = "  both  ".parse().unwrap();
+    t.trim();
+    assert_eq!(t, "both");
+    assert_eq!(t.as_str().trim_start_matches('b'), "oth");
+
+    s.replace_range(0..5, "goodbye").unwrap();
+    assert_eq!(s, "goodbye world");
+    s.replace_range(7.., "").unwrap();
+    assert_eq!(s, "goodbye");
+    assert_eq!(s.replace_range(..1, "x".repeat(40).as_str()), Err(StackedError::Overflow{ cap:32, rejected:14 }));
+    assert_eq!(s.replace_range(3..9, "x"), Err(StackedError::OutOfBounds{ index:9, len:7 }));
+    assert_eq!(s, "goodbye");
+
+    let mut u : SString8 = "año".parse().unwrap();
+    assert_eq!(u.replace_range(..2, "a"), Err(StackedError::NotCharBoundary{ index:2 }));
+    u.replace_range(1..3, "nn").unwrap();
+    assert_eq!(u, "anno");
+
+    let r = s.replace("o", "00").unwrap();
+    assert_eq!(r, "g0000dbye");
+    assert_eq!(s.replace("bye", "").unwrap(), "good");
+    assert_eq!(s.replace("xyz", "!").unwrap(), "goodbye");
+    let small : SString8 = "aaaa".parse().unwrap();
+    assert_eq!(small.replace("a", "bbb"), Err(StackedError::Overflow{ cap:8, rejected:4 }));
+
+    let mut m : SString16 = "MiXeD ñ".parse().unwrap();
+    m.make_ascii_lowercase();
+    assert_eq!(m, "mixed ñ");
+    m.make_ascii_uppercase();
+    assert_eq!(m, "MIXED ñ");
+
+    let line : SString32 = "GET /index.html HTTP/1.1".parse().unwrap();
+    let words = line.split_into::<SVec4<&str>>(" ").unwrap();
+    assert_eq!(words, ["GET", "/index.html", "HTTP/1.1"]);
+    let csv : SString32 = "a,b,,c,d,e".parse().unwrap();
+    assert_eq!(csv.split_into::<SVec8<&str>>(",").unwrap(), ["a","b","","c","d","e"]);
+    assert_eq!(csv.split_into::<SVec4<&str>>(",").unwrap_err(), StackedError::Overflow{ cap:4, rejected:2 });
+}
+
+#[test]
+fn truncating() {
+    use stacked::{Truncation, SString256};
+
+    let mut s = SString8::new();
+    assert_eq!(s.push_str_truncating("abc"), Truncation{ written:3, truncated:false });
+    assert_eq!(s.push_str_truncating("défgh"), Truncation{ written:5, truncated:true });
+    assert_eq!(s, "abcdéfg");  // 'é' is 2 bytes, so that's all 8.
+    assert_eq!(s.push_str_truncating("x"), Truncation{ written:0, truncated:true });
+
+    // Never splits a char:
+    let (s, t) = SString4::from_str_truncating("ab€");  // '€' is 3 bytes.
+    assert_eq!((s.as_str(), t), ("ab", Truncation{ written:2, truncated:true }));
+
+    // Avoids splitting a grapheme cluster:  "e" + U+0301 (combining acute) is one visible character.
+    let (s, t) = SString4::from_str_truncating("abe\u{301}");
+    assert_eq!((s.as_str(), t), ("ab", Truncation{ written:2, truncated:true }));
+    let (s, _) = SString16::from_str_truncating("hi 👍🏽👍🏽");  // Emoji + skin tone modifier:  8 bytes each.
+    assert_eq!(s, "hi 👍🏽");
+
+    // ...unless not even the first cluster fits:
+    let (s, t) = SString2::from_str_truncating("e\u{301}\u{301}");
+    assert_eq!((s.as_str(), t), ("e", Truncation{ written:1, truncated:true }));
+
+    let (s, t) = SString256::from_str_truncating("short");
+    assert_eq!((s.as_str(), t), ("short", Truncation{ written:5, truncated:false }));
+}
@@ -1,12 +1,10 @@
-#![feature(backtrace, box_syntax)]
+use stacked::{SVec, StackedError, StackVec, SVec2, SVec4, SVec8, SVec16, SVec8192};
 
-use stacked::{SVec, SVec4, SVec16, SVec8192};
-
-use kerr::KErr;
 
 use std::mem::size_of;
 use std::backtrace::Backtrace;
 use std::time::Instant;
+use std::thread;
 
 #[test]
 fn svec1() {
@@ -17,12 +15,12 @@ fn svec1() {
     vec.push(2).unwrap();
     vec.push(3).unwrap();
 
-    assert_eq!(vec.push(4), Err(KErr::new("overflow")));
+    assert_eq!(vec.push(4), Err((4, StackedError::Overflow{ cap:4, rejected:1 })));
 }
 
 
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Default)]
 struct Dropper(i32);
 impl Drop for Dropper {
     fn drop(&mut self) {
@@ -30,9 +28,6 @@ impl Drop for Dropper {
         if self.0%10==0 { eprintln!("{}",Backtrace::capture()); }
     }
 }
-impl Default for Dropper {
-    fn default() -> Self { Self(0) }
-}
 
 #[test]
 fn svec2() {
@@ -124,7 +119,7 @@ fn mutation() {
     assert_eq!(vec.to_string(), "[ 35, 38, 39 ]");
     assert_eq!(format!("{:?}",vec), "SVec4[ 35, 38, 39 ]");
 
-    //for x in vec {}  // Nice error message.
+    for x in vec { assert!(x>30); }  // Consumes vec.
 }
 
 #[test]
@@ -147,18 +142,18 @@ fn partialeq() {
     let mut a = SVec4::<u8>::new();
     let mut b = SVec16::<u8>::new();
 
-    assert_eq!(a.eq(&b), true);
-    assert_eq!(b.eq(&a), true);
+    assert!(a.eq(&b));
+    assert!(b.eq(&a));
 
     a.push(0).unwrap();
 
-    assert_eq!(a.eq(&b), false);
-    assert_eq!(b.eq(&a), false);
+    assert!(!a.eq(&b));
+    assert!(!b.eq(&a));
 
     b.push(0).unwrap();
 
-    assert_eq!(a.eq(&b), true);
-    assert_eq!(b.eq(&a), true);
+    assert!(a.eq(&b));
+    assert!(b.eq(&a));
 }
 
 #[test]
@@ -176,7 +171,7 @@ fn fromiter() {
     // Array does NOT iterate owned values:
     {
         let a = [Dropper(20), Dropper(21)];
-        #[allow(array_into_iter)]
+        #[allow(array_into_iter, clippy::into_iter_on_ref)]
         for x in a.into_iter() {
             println!("in a loop: {}",x.0);
         }
@@ -200,6 +195,7 @@ fn fromiter() {
         println!("addr of s: {:?}  dataptr: {:?}", &s as *const _, "s.dataptr()");
         s.push(Dropper(40)).unwrap();
         s.push(Dropper(41)).unwrap();
+        #[allow(clippy::never_loop)]
         for x in s.iter_owned() {
             println!("in s2 loop: {}",x.0);
             break;
@@ -230,6 +226,7 @@ fn fromiter() {
             println!("addr of intoiter1: {:?}  dataptr: {:?}", &intoiter1 as *const _, "intoiter1.dataptr()");
         //    intoiter1    // Yay, the compiler prevents us from returning this iterator out of the function.
         }
+        #[allow(clippy::let_unit_value)]
         let _intoiter2 = output_an_intoiter();
         println!("after drop of svec -- only intoiter remains...");
         //println!("addr of intoiter2: {:?}  dataptr: {:?}", &_intoiter2 as *const _, "_intoiter2.dataptr()");
@@ -271,6 +268,10 @@ fn collect() {
 
 #[test]
 fn boxed() {
+    // SVec8192<[u8;256]> is bigger than the default 2MiB test-thread stack, so give ourselves more room:
+    thread::Builder::new().stack_size(64<<20).spawn(boxed_inner).unwrap().join().unwrap();
+}
+fn boxed_inner() {
     const SIZE : usize = 256;
 
     let start = Instant::now();
@@ -288,7 +289,7 @@ fn boxed() {
         let start = Instant::now();
         #[allow(unused_variables)]
         for i in 0..10000 {
-            let mut v = box SVec8192::<[u8;SIZE]>::new();
+            let mut v = Box::new(SVec8192::<[u8;SIZE]>::new());
             for j in 0..v.cap() {
                 v.push([j as u8;SIZE]).unwrap();
             }
@@ -309,3 +310,464 @@ fn boxed() {
     }
 }
 
+
+#[test]
+fn any_size() {
+    let mut v = StackVec::<i32,100>::new();
+    assert_eq!(v.cap(), 100);
+    assert_eq!(StackVec::<i32,300>::cap_of_type(), 300);
+    while v.len()<v.cap() { v.push(v.len() as i32).unwrap(); }
+    assert_eq!(v.push(100), Err((100, StackedError::Overflow{ cap:100, rejected:1 })));
+    assert_eq!(v[99], 99);
+
+    let w = v.new_of::<u8>();
+    assert_eq!(w.cap(), 100);
+
+    let a : SVec16<i32> = SVec16::new();
+    let b : StackVec<i32,16> = a;  // The old names are just aliases.
+    assert_eq!(b.cap(), 16);
+}
+
+#[test]
+fn drops_once() {
+    use std::rc::Rc;
+    let token = Rc::new(());
+    {
+        let mut v = SVec8::<Rc<()>>::new();
+        for _ in 0..5 { v.push(token.clone()).unwrap(); }
+        assert_eq!(Rc::strong_count(&token), 6);
+        v.remove(1);
+        v.insert(0, token.clone());
+        assert_eq!(Rc::strong_count(&token), 6);
+        v.pop();
+        assert_eq!(Rc::strong_count(&token), 5);
+        v.clear();
+        assert_eq!(Rc::strong_count(&token), 1);
+        v.push(token.clone()).unwrap();
+        v.push(token.clone()).unwrap();
+    }
+    assert_eq!(Rc::strong_count(&token), 1);
+}
+
+#[test]
+fn slices() {
+    let mut v : SVec16<i32> = vec![5,3,9,1].into();
+    assert_eq!(v.as_slice(), &[5,3,9,1]);
+
+    v.sort();
+    assert_eq!(v.as_slice(), &[1,3,5,9]);
+    assert_eq!(v.binary_search(&5), Ok(2));
+    assert!(v.contains(&9));
+    assert!(v.starts_with(&[1,3]));
+    assert_eq!(v.chunks(3).count(), 2);
+    assert_eq!(v.to_vec(), vec![1,3,5,9]);
+
+    v.as_mut_slice()[0] = 7;
+    v[1..3].copy_from_slice(&[8,8]);
+    assert_eq!(&*v, &[7,8,8,9]);
+
+    fn total(xs:&[i32]) -> i32 { xs.iter().sum() }
+    assert_eq!(total(&v), 32);
+
+    let empty = SVec4::<i32>::new();
+    assert_eq!(empty.as_slice(), &[] as &[i32]);
+}
+
+#[test]
+fn into_iter_owned() {
+    let v : SVec8<String> = vec!["a".to_string(), "b".to_string(), "c".to_string()].into();
+    let mut out = Vec::new();
+    for s in v { out.push(s); }
+    assert_eq!(out, vec!["a", "b", "c"]);
+
+    let v : SVec8<i32> = [1,2,3,4,5].into();
+    let mut it = v.into_iter();
+    assert_eq!(it.len(), 5);
+    assert_eq!(it.next(), Some(1));
+    assert_eq!(it.next_back(), Some(5));
+    assert_eq!(it.as_slice(), &[2,3,4]);
+    assert_eq!(it.len(), 3);
+    assert_eq!(it.rev().collect::<Vec<_>>(), vec![4,3,2]);
+
+    // Items that are not consumed still get dropped:
+    use std::rc::Rc;
+    let token = Rc::new(());
+    let mut v = SVec4::<Rc<()>>::new();
+    for _ in 0..4 { v.push(token.clone()).unwrap(); }
+    let mut it = v.into_iter();
+    let first = it.next();
+    assert_eq!(Rc::strong_count(&token), 5);
+    drop(it);
+    assert_eq!(Rc::strong_count(&token), 2);
+    drop(first);
+    assert_eq!(Rc::strong_count(&token), 1);
+}
+
+#[test]
+fn concrete_iters() {
+    let mut v : SVec16<i32> = vec![1,2,3,4,5].into();
+
+    let mut it = v.iter();
+    assert_eq!(it.len(), 5);
+    assert_eq!(it.nth(1), Some(&2));
+    assert_eq!(it.next_back(), Some(&5));
+    assert_eq!(it.as_slice(), &[3,4]);
+    assert_eq!(it.clone().rev().collect::<Vec<_>>(), vec![&4,&3]);
+    assert_eq!(it.next(), Some(&3));
+    assert_eq!(it.next(), Some(&4));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);  // Fused.
+
+    for x in v.iter_mut().rev().take(2) { *x*=10; }
+    assert_eq!(v.as_slice(), &[1,2,3,40,50]);
+    assert_eq!((&mut v).into_iter().len(), 5);
+
+    let mut owned = v.iter_owned();
+    assert_eq!(owned.len(), 5);
+    assert_eq!(owned.next(), Some(50));
+    assert_eq!(owned.next(), Some(40));
+    drop(owned);
+    assert_eq!(v.as_slice(), &[1,2,3]);  // The rest stay behind.
+
+    v.extend_from_slice(&[4,5,6,7]).unwrap();
+    let mut owned = v.iter_owned();
+    assert_eq!(owned.next_back(), Some(1));  // From the front.
+    assert_eq!(owned.nth(1), Some(6));       // Drops 7.
+    assert_eq!(owned.nth_back(1), Some(3));  // Drops 2.
+    assert_eq!(owned.as_slice(), &[4,5]);
+    drop(owned);
+    assert_eq!(v.as_slice(), &[4,5]);        // The leftovers get moved back to the start.
+    assert_eq!(v.iter_owned().rev().collect::<Vec<_>>(), vec![4,5]);
+    assert!(v.is_empty());
+
+    use std::rc::Rc;
+    let token = Rc::new(());
+    let mut v = SVec8::<Rc<()>>::new();
+    for _ in 0..6 { v.push(token.clone()).unwrap(); }
+    assert!(v.iter_owned().nth(3).is_some());
+    assert_eq!((v.len(), Rc::strong_count(&token)), (2, 3));
+    assert!(v.iter_owned().nth_back(5).is_none());  // Past the end:  drops everything.
+    assert_eq!((v.len(), Rc::strong_count(&token)), (0, 1));
+}
+
+#[test]
+fn errors() {
+    let mut v = SVec2::<String>::new();
+    v.push("a".to_string()).unwrap();
+    v.push("b".to_string()).unwrap();
+    let (rejected, err) = v.push("c".to_string()).unwrap_err();
+    assert_eq!(rejected, "c");  // Nothing is lost on overflow.
+    assert_eq!(err, StackedError::Overflow{ cap:2, rejected:1 });
+    assert_eq!(err.to_string(), "overflow: capacity is 2, 1 item(s) rejected");
+
+    fn fill() -> Result<SVec2<i32>, StackedError> {
+        let mut v = SVec2::new();
+        v.push(1)?;
+        v.push(2)?;
+        v.push(3)?;
+        Ok(v)
+    }
+    assert_eq!(fill().unwrap_err(), StackedError::Overflow{ cap:2, rejected:1 });
+    assert_eq!(SVec2::<i32>::try_from_iter(0..3).unwrap_err(), StackedError::Overflow{ cap:2, rejected:1 });
+
+    let bytes : SVec8<u8> = vec![b'o', b'k', 0xff].into();
+    assert_eq!(bytes.as_string(), Err(StackedError::InvalidUtf8{ valid_up_to:2 }));
+
+    let boxed : Box<dyn std::error::Error> = Box::new(StackedError::OutOfBounds{ index:5, len:3 });
+    assert_eq!(boxed.to_string(), "out-of-bounds: index is 5 but len is 3");
+}
+
+#[test]
+fn fallible() {
+    let mut v = SVec2::<i32>::new();
+    assert_eq!(v.try_pop(), None);
+    assert_eq!(v.try_remove(0), None);
+    assert_eq!(v.first(), None);
+    assert_eq!(v.last(), None);
+    assert_eq!(v.get(0), None);
+
+    assert_eq!(v.try_insert(1, 10), Err((10, StackedError::OutOfBounds{ index:1, len:0 })));
+    v.try_insert(0, 10).unwrap();
+    v.try_insert(0, 20).unwrap();
+    assert_eq!(v.try_insert(0, 30), Err((30, StackedError::Overflow{ cap:2, rejected:1 })));
+    assert_eq!(v.as_slice(), &[20,10]);
+
+    assert_eq!(v.first(), Some(&20));
+    assert_eq!(v.last(), Some(&10));
+    assert_eq!(v.get(1), Some(&10));
+    assert_eq!(v.get(2), None);
+    *v.get_mut(1).unwrap() = 11;
+    assert_eq!(v.get_mut(2), None);
+
+    assert_eq!(v.try_remove(2), None);
+    assert_eq!(v.try_remove(0), Some(20));
+    assert_eq!(v.try_pop(), Some(11));
+    assert_eq!(v.try_pop(), None);
+}
+
+#[test]
+#[should_panic(expected="underflow")]
+fn pop_underflow() {
+    SVec2::<i32>::new().pop();
+}
+
+#[test]
+fn drain() {
+    let mut v : SVec16<i32> = (0..10).collect();
+    let batch : Vec<i32> = v.drain(..4).collect();
+    assert_eq!(batch, vec![0,1,2,3]);
+    assert_eq!(v.as_slice(), &[4,5,6,7,8,9]);
+
+    // Like std's Drain, these can cross threads (e.g. inside a Send future) when the items can:
+    fn is_send_sync<T:Send+Sync>(_:&T) {}
+    is_send_sync(&v.drain(..0));
+    is_send_sync(&v.iter_owned());
+
+    {
+        let mut d = v.drain(1..=3);
+        assert_eq!(d.len(), 3);
+        assert_eq!(d.next_back(), Some(7));
+        assert_eq!(d.as_slice(), &[5,6]);
+        // The rest get dropped along with the Drain.
+    }
+    assert_eq!(v.as_slice(), &[4,8,9]);
+
+    v.drain(3..);
+    assert_eq!(v.as_slice(), &[4,8,9]);
+    v.drain(..);
+    assert!(v.is_empty());
+
+    // Leaking the Drain leaves the SVec truncated, but consistent:
+    use std::rc::Rc;
+    let token = Rc::new(());
+    let mut v = SVec8::<Rc<()>>::new();
+    for _ in 0..6 { v.push(token.clone()).unwrap(); }
+    std::mem::forget(v.drain(2..4));
+    assert_eq!(v.len(), 2);
+    drop(v);
+    assert_eq!(Rc::strong_count(&token), 5);  // 4 leaked, but nothing double-dropped.
+}
+
+#[test]
+fn drain_panicking_drop() {
+    use std::cell::Cell;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    thread_local!(static DROPS : Cell<usize> = const { Cell::new(0) });
+    #[derive(Debug)]
+    struct Bomb(bool);
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            DROPS.with(|d| d.set(d.get()+1));
+            if self.0 { panic!("boom"); }
+        }
+    }
+
+    let mut v = SVec8::<Bomb>::new();
+    for i in 0..6 { v.push(Bomb(i==2)).unwrap(); }
+    let result = catch_unwind(AssertUnwindSafe(|| { v.drain(1..4); }));
+    assert!(result.is_err());
+    assert_eq!(DROPS.with(|d| d.get()), 3);  // Every drained item was dropped exactly once...
+    assert_eq!(v.len(), 3);                   // ...and the tail was still moved into place.
+    drop(v);
+    assert_eq!(DROPS.with(|d| d.get()), 6);
+}
+
+#[test]
+fn retain_dedup() {
+    let mut v : SVec16<i32> = (0..10).collect();
+    v.retain(|x| x%3!=0);
+    assert_eq!(v.as_slice(), &[1,2,4,5,7,8]);
+    v.retain_mut(|x| { *x*=10; *x<60 });
+    assert_eq!(v.as_slice(), &[10,20,40,50]);
+    v.retain(|_| false);
+    assert!(v.is_empty());
+
+    let mut v : SVec16<i32> = vec![1,1,2,3,3,3,1,4,4].into();
+    v.dedup();
+    assert_eq!(v.as_slice(), &[1,2,3,1,4]);
+
+    let mut v : SVec16<i32> = vec![10,11,20,25,31,9].into();
+    v.dedup_by_key(|x| *x/10);
+    assert_eq!(v.as_slice(), &[10,20,31,9]);
+
+    let mut v : SVec8<&str> = vec!["a","A","b","B","b"].into();
+    v.dedup_by(|a,b| a.eq_ignore_ascii_case(b));
+    assert_eq!(v.as_slice(), &["a","b"]);
+
+    // Removed items are dropped exactly once:
+    use std::rc::Rc;
+    let token = Rc::new(());
+    let mut v = SVec8::<(i32,Rc<()>)>::new();
+    for i in [1,1,2,2,3,4] { v.push((i, token.clone())).unwrap(); }
+    v.dedup_by_key(|x| x.0);
+    assert_eq!(Rc::strong_count(&token), 5);
+    v.retain(|x| x.0%2==1);
+    assert_eq!(Rc::strong_count(&token), 3);
+    assert_eq!(v.iter().map(|x| x.0).collect::<Vec<_>>(), vec![1,3]);
+}
+
+#[test]
+fn retain_panicking_predicate() {
+    use std::rc::Rc;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    let token = Rc::new(());
+    let mut v = SVec8::<(i32,Rc<()>)>::new();
+    for i in 0..6 { v.push((i, token.clone())).unwrap(); }
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        v.retain(|x| if x.0==3 { panic!("boom") } else { x.0%2==0 });
+    }));
+    assert!(result.is_err());
+    assert_eq!(v.iter().map(|x| x.0).collect::<Vec<_>>(), vec![0,2,3,4,5]);
+    assert_eq!(Rc::strong_count(&token), 6);
+}
+
+#[test]
+fn extend() {
+    let mut v : SVec8<i32> = vec![1,2].into();
+    v.extend(vec![3,4]);
+    v.extend(&[5,6]);
+    assert_eq!(v.as_slice(), &[1,2,3,4,5,6]);
+
+    assert_eq!(v.try_extend(std::iter::empty()).unwrap(), 0);
+    let err = v.try_extend(7..12).unwrap_err();
+    assert_eq!(err.accepted, 2);
+    assert_eq!(err.error, StackedError::Overflow{ cap:8, rejected:3 });
+    assert_eq!(err.leftover.collect::<Vec<_>>(), vec![9,10,11]);  // Nothing was lost.
+    assert_eq!(v.as_slice(), &[1,2,3,4,5,6,7,8]);
+
+    let mut v = SVec4::<String>::new();
+    v.extend_from_slice(&["a".to_string()]).unwrap();
+    let words = ["b".to_string(), "c".to_string(), "d".to_string(), "e".to_string()];
+    let err = v.extend_from_slice(&words).unwrap_err();
+    assert_eq!(err.accepted, 3);
+    assert_eq!(err.leftover, &["e".to_string()]);
+    assert_eq!(err.error, StackedError::Overflow{ cap:4, rejected:1 });
+    assert_eq!(v.as_slice(), &["a","b","c","d"]);
+
+    fn add_all(v:&mut SVec4<String>, more:&[String]) -> Result<(), StackedError> {
+        v.extend_from_slice(more)?;
+        Ok(())
+    }
+    assert!(add_all(&mut v, &[]).is_ok());
+    assert!(add_all(&mut v, &words).is_err());
+}
+
+#[test]
+#[should_panic(expected="overflow")]
+fn extend_overflow() {
+    let mut v = SVec2::<i32>::new();
+    v.extend(0..3);
+}
+
+#[test]
+fn reshape() {
+    let mut v : SVec8<i32> = (0..6).collect();
+    v.truncate(10);
+    assert_eq!(v.len(), 6);
+    v.truncate(4);
+    assert_eq!(v.as_slice(), &[0,1,2,3]);
+
+    assert_eq!(v.swap_remove(1), 1);
+    assert_eq!(v.as_slice(), &[0,3,2]);
+    assert_eq!(v.swap_remove(2), 2);
+    assert_eq!(v.as_slice(), &[0,3]);
+    assert_eq!(v.try_swap_remove(2), None);
+
+    v.resize(5, 7).unwrap();
+    assert_eq!(v.as_slice(), &[0,3,7,7,7]);
+    assert_eq!(v.resize(9, 7), Err(StackedError::Overflow{ cap:8, rejected:1 }));
+    assert_eq!(v.len(), 5);  // Unchanged.
+    v.resize(2, 7).unwrap();
+    assert_eq!(v.as_slice(), &[0,3]);
+
+    let mut n = 10;
+    v.resize_with(4, || { n+=1; n }).unwrap();
+    assert_eq!(v.as_slice(), &[0,3,11,12]);
+    assert!(v.resize_with(20, || 0).is_err());
+
+    let tail = v.split_off(1);
+    assert_eq!(v.as_slice(), &[0]);
+    assert_eq!(tail.as_slice(), &[3,11,12]);
+    assert_eq!(tail.cap(), 8);
+
+    let mut words : SVec4<String> = vec!["a".to_string(), "b".to_string()].into();
+    let rest = words.split_off(2);
+    assert!(rest.is_empty());
+    words.truncate(0);
+    assert!(words.is_empty());
+}
+
+#[test]
+fn std_traits() {
+    use std::collections::{HashMap, BTreeMap};
+
+    let a : SVec8<String> = vec!["x".to_string(), "y".to_string()].into();
+    let b = a.clone();
+    assert_eq!(a, b);
+
+    let mut c : SVec8<String> = vec!["p".to_string(), "q".to_string(), "r".to_string()].into();
+    c.clone_from(&a);
+    assert_eq!(c, a);
+
+    #[derive(Default)]
+    struct Holder { v:SVec4<i32> }
+    assert!(Holder::default().v.is_empty());
+
+    let mut hm = HashMap::new();
+    hm.insert(SVec8::<u8>::from(*b"key"), 1);
+    assert_eq!(hm.get(&b"key"[..]), Some(&1));  // Hash matches the slice's.
+
+    let mut bm = BTreeMap::new();
+    bm.insert(SVec4::<i32>::from([2,1]), "b");
+    bm.insert(SVec4::<i32>::from([1,9]), "a");
+    bm.insert(SVec4::<i32>::from([1]), "0");
+    assert_eq!(bm.values().cloned().collect::<Vec<_>>(), vec!["0","a","b"]);
+
+    let x = SVec4::<i32>::from([1,2]);
+    let y = SVec16::<i32>::from([1,3]);
+    assert!(x<y);
+    assert_eq!(x.cmp(&x.clone()), std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn cross_type_eq() {
+    let v : SVec8<i32> = vec![1,2,3].into();
+    assert_eq!(v, vec![1,2,3]);
+    assert_eq!(vec![1,2,3], v);
+    assert_eq!(v, [1,2,3]);
+    assert_eq!([1,2,3], v);
+    assert_eq!(v, &[1,2,3]);
+    assert_eq!(v, &[1,2,3][..]);
+    assert_eq!(&[1,2,3][..], v);
+    assert_eq!(v[..], [1,2,3][..]);
+    assert!(v!=[1,2]);
+    assert!(v!=vec![1,2,4]);
+
+    let s : SVec8<u8> = (*b"abc").into();
+    assert_eq!(s, &b"abc"[..]);
+    assert_eq!(s, b"abc");
+    assert_eq!(b"abc", s);
+
+    // Differing element types, as long as they're comparable:
+    let strs : SVec4<&str> = vec!["a","b"].into();
+    let strings : SVec16<String> = vec!["a".to_string(), "b".to_string()].into();
+    assert_eq!(strings, strs);
+    assert_eq!(strings, ["a","b"]);
+    assert_eq!(vec!["a","b"], strings);
+}
+
+#[test]
+fn as_str() {
+    let mut bytes : SVec16<u8> = (*b"Hello").into();
+    assert_eq!(bytes.as_str().unwrap(), "Hello");
+    bytes.as_str_mut().unwrap().make_ascii_uppercase();
+    assert_eq!(bytes.as_str(), Ok("HELLO"));
+    assert_eq!(bytes.to_string_lossy(), "HELLO");
+    assert!(matches!(bytes.to_string_lossy(), std::borrow::Cow::Borrowed(_)));  // No copy when it's valid.
+
+    bytes.push(0xff).unwrap();
+    assert_eq!(bytes.as_str().unwrap_err().valid_up_to(), 5);
+    assert!(bytes.as_str_mut().is_err());
+    assert_eq!(bytes.to_string_lossy(), "HELLO\u{FFFD}");
+}